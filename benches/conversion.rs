@@ -0,0 +1,136 @@
+//! Criterion benchmarks for parsing, subset construction, minimization and search.
+//! Run with `cargo bench --features test-util`.
+
+use std::hint::black_box;
+use std::str::FromStr;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use fa_viz::bytes::ByteDfa;
+use fa_viz::testing::{RandomNfaConfig, Rng, random_nfa};
+use fa_viz::{LazyDfa, NFA};
+
+/// "The `n`th symbol from the end is `a`", whose minimal DFA has 2^n states
+fn nth_from_end(n: usize) -> NFA {
+    let mut definition = format!("{} & a b\n- 0 0 a b\n- 0 1 a\n", n + 1);
+    for i in 1..n {
+        definition += &format!("- {} {} a b\n", i, i + 1);
+    }
+    definition += &format!("+ {} {}\n", n, n);
+    NFA::from_str(&definition).expect("generated definition parses")
+}
+
+fn random(states: usize) -> NFA {
+    random_with_lambdas(states, 0.5)
+}
+
+/// Random NFA with `lambdas` lambda transitions per state on average
+fn random_with_lambdas(states: usize, lambdas: f64) -> NFA {
+    let config = RandomNfaConfig {
+        states,
+        transition_density: 2.0 / states as f64,
+        lambda_density: lambdas / states as f64,
+        ..Default::default()
+    };
+    random_nfa(&config, &mut Rng::new(states as u64))
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for states in [100, 1000] {
+        let definition = random(states).serialize();
+        group.throughput(Throughput::Bytes(definition.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(states),
+            &definition,
+            |b, definition| b.iter(|| NFA::from_str(black_box(definition))),
+        );
+    }
+    group.finish();
+}
+
+fn determinize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("determinize");
+    for n in [6, 10, 14] {
+        let nfa = nth_from_end(n);
+        group.bench_with_input(BenchmarkId::new("nth_from_end", n), &nfa, |b, nfa| {
+            b.iter(|| black_box(nfa).determinize())
+        });
+    }
+    for states in [50, 200] {
+        let nfa = random(states);
+        group.bench_with_input(BenchmarkId::new("random", states), &nfa, |b, nfa| {
+            b.iter(|| black_box(nfa).determinize())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("random_optimized", states),
+            &nfa,
+            |b, nfa| b.iter(|| black_box(nfa).optimize().determinize()),
+        );
+    }
+    group.finish();
+}
+
+fn minimize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("minimize_brzozowski");
+    group.sample_size(20);
+    for states in [50, 200] {
+        let nfa = random(states).determinize();
+        let nfa = NFA::from(&nfa);
+        group.bench_with_input(BenchmarkId::from_parameter(states), &nfa, |b, nfa| {
+            b.iter(|| black_box(nfa).minimize_brzozowski())
+        });
+    }
+    group.finish();
+}
+
+fn simulate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("accepts");
+    let input: String = "abbabaab".chars().cycle().take(1 << 12).collect();
+    for states in [100, 1000] {
+        let nfa = random(states);
+        group.bench_with_input(BenchmarkId::from_parameter(states), &nfa, |b, nfa| {
+            b.iter(|| nfa.accepts(black_box(&input)))
+        });
+    }
+    // Every step takes the lambda closure of the states reached
+    for states in [100, 1000] {
+        let nfa = random_with_lambdas(states, 2.0);
+        group.bench_with_input(BenchmarkId::new("lambdas", states), &nfa, |b, nfa| {
+            b.iter(|| nfa.accepts(black_box(&input)))
+        });
+    }
+    // Determinizing upfront would build 2^16 states
+    let nfa = nth_from_end(16);
+    group.bench_function("lazy_nth_from_end_16", |b| {
+        b.iter(|| LazyDfa::new(&nfa).accepts(black_box(&input)))
+    });
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    let nfa = nth_from_end(4);
+    let table = nfa.determinize();
+    let haystack: Vec<char> = "abba baab bbbb aaaa\n"
+        .chars()
+        .cycle()
+        .take(1 << 20)
+        .collect();
+    group.throughput(Throughput::Elements(haystack.len() as u64));
+    group.sample_size(10);
+    group.bench_function("nfa_find_matches", |b| {
+        b.iter(|| nfa.find_matches(black_box(&haystack), false))
+    });
+    group.bench_function("table_find_iter", |b| {
+        b.iter(|| table.find_iter(black_box(&haystack)).count())
+    });
+    let dfa = ByteDfa::new(&table).expect("alphabet is ASCII");
+    let bytes: String = haystack.iter().collect();
+    group.bench_function("byte_dfa_find_iter", |b| {
+        b.iter(|| dfa.find_iter(black_box(bytes.as_bytes())).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, determinize, minimize, simulate, search);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Compare a submitted automaton against a reference by checking every string up to a length.
+//!
+//! `cargo run --example grade -- reference.dat submission.dat [max_len]`
+
+use anyhow::{Context, Result, bail};
+use fa_viz::{NFA, frontend};
+
+fn load(path: &str) -> Result<NFA> {
+    let input =
+        std::fs::read_to_string(path).with_context(|| format!("Unable to read {}", path))?;
+    let frontend = frontend::detect(&input).context("Unable to detect input format")?;
+    Ok(frontend.parse(&input)?.to_nfa())
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [reference, submission, rest @ ..] = args.as_slice() else {
+        bail!("usage: grade <reference> <submission> [max_len]");
+    };
+    let max_len = match rest.first() {
+        Some(n) => n.parse::<usize>().context("max_len must be a number")?,
+        None => 8,
+    };
+    let (reference, submission) = (load(reference)?, load(submission)?);
+
+    let missing = reference
+        .enumerate_accepted(max_len)
+        .find(|w| !submission.accepts(w));
+    let extra = submission
+        .enumerate_accepted(max_len)
+        .find(|w| !reference.accepts(w));
+
+    match (missing, extra) {
+        (None, None) => println!("PASS: agrees on all strings up to length {}", max_len),
+        (missing, extra) => {
+            if let Some(w) = missing {
+                println!("FAIL: rejects {:?}", w);
+            }
+            if let Some(w) = extra {
+                println!("FAIL: accepts {:?}", w);
+            }
+        }
+    }
+
+    Ok(())
+}
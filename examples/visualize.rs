@@ -0,0 +1,28 @@
+//! Build an NFA from an in-memory definition, print its DOT graph and a few accepted strings.
+//!
+//! `cargo run --example visualize | dot -Tsvg > nfa.svg`
+
+use std::str::FromStr;
+
+use fa_viz::{Digraph, NFA};
+
+const DEFINITION: &str = "\
+4 # a b
+- 0 1 a
+- 1 1 a
+- 1 2 b
+- 2 1 #
++ 2 3 b
++ 3 3
+";
+
+fn main() -> anyhow::Result<()> {
+    let nfa = NFA::from_str(DEFINITION)?;
+    println!("{}", Digraph::from(&nfa).mark_useless());
+
+    for word in nfa.enumerate_accepted(6).take(5) {
+        eprintln!("accepts {:?}", word);
+    }
+
+    Ok(())
+}
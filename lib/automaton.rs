@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use transition_tables::TransitionTable;
+
+use crate::{
+    Digraph, LabelStyle, LabeledTable, NFA, Transition, TransitionTableExt, table::column_symbol,
+};
+
+/// The shape shared by every finite automaton, so analyses can be written once for NFAs and
+/// transition tables alike. States are plain ids, a DFA's edges are never lambda.
+pub trait FiniteAutomaton {
+    fn start_state(&self) -> Option<usize>;
+
+    /// Every state, including ones only mentioned as a target
+    fn state_ids(&self) -> BTreeSet<usize>;
+
+    /// Column order for tables
+    fn symbols(&self) -> Vec<char>;
+
+    fn is_accepting_state(&self, state: usize) -> bool;
+
+    /// Every `(from, transition, to)`
+    fn edges(&self) -> Vec<(usize, Transition, usize)>;
+
+    /// States reachable from the start state along any edges
+    fn reachable_states(&self) -> BTreeSet<usize> {
+        let mut successors: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (from, _, to) in self.edges() {
+            successors.entry(from).or_default().push(to);
+        }
+        search(self.start_state().into_iter().collect(), &successors)
+    }
+
+    /// States from which some accepting state can be reached
+    fn live_states(&self) -> BTreeSet<usize> {
+        let mut predecessors: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (from, _, to) in self.edges() {
+            predecessors.entry(to).or_default().push(from);
+        }
+        let accepting = self
+            .state_ids()
+            .into_iter()
+            .filter(|s| self.is_accepting_state(*s))
+            .collect();
+        search(accepting, &predecessors)
+    }
+}
+
+/// Everything reachable from `roots` in `adjacency`, the roots included
+fn search(roots: Vec<usize>, adjacency: &BTreeMap<usize, Vec<usize>>) -> BTreeSet<usize> {
+    let mut seen: BTreeSet<usize> = roots.iter().copied().collect();
+    let mut stack = roots;
+    while let Some(state) = stack.pop() {
+        for next in adjacency.get(&state).into_iter().flatten() {
+            if seen.insert(*next) {
+                stack.push(*next);
+            }
+        }
+    }
+    seen
+}
+
+impl FiniteAutomaton for NFA {
+    fn start_state(&self) -> Option<usize> {
+        self.start()
+    }
+
+    fn state_ids(&self) -> BTreeSet<usize> {
+        self.states()
+            .into_iter()
+            .flat_map(|(s, (_, transitions))| {
+                std::iter::once(s).chain(transitions.into_values().flatten())
+            })
+            .collect()
+    }
+
+    fn symbols(&self) -> Vec<char> {
+        self.alphabet().to_vec()
+    }
+
+    fn is_accepting_state(&self, state: usize) -> bool {
+        self.is_accepting(state)
+    }
+
+    fn edges(&self) -> Vec<(usize, Transition, usize)> {
+        self.states()
+            .into_iter()
+            .flat_map(|(from, (_, transitions))| {
+                transitions
+                    .into_iter()
+                    .flat_map(move |(t, targets)| targets.into_iter().map(move |to| (from, t, to)))
+            })
+            .collect()
+    }
+}
+
+/// Columns are named `a`, `b`, ... like the plain table format implies
+impl FiniteAutomaton for TransitionTable {
+    fn start_state(&self) -> Option<usize> {
+        self.rows.first().map(|r| r.id)
+    }
+
+    fn state_ids(&self) -> BTreeSet<usize> {
+        self.rows
+            .iter()
+            .flat_map(|r| std::iter::once(r.id).chain(r.transitions.iter().flatten().copied()))
+            .collect()
+    }
+
+    fn symbols(&self) -> Vec<char> {
+        (0..self.alphabet_size()).map(column_symbol).collect()
+    }
+
+    fn is_accepting_state(&self, state: usize) -> bool {
+        self.row(state).is_some_and(|r| r.accepting)
+    }
+
+    fn edges(&self) -> Vec<(usize, Transition, usize)> {
+        self.rows
+            .iter()
+            .flat_map(|r| {
+                r.transitions.iter().enumerate().filter_map(|(column, to)| {
+                    to.map(|to| (r.id, Transition::Char(column_symbol(column)), to))
+                })
+            })
+            .collect()
+    }
+}
+
+impl FiniteAutomaton for LabeledTable {
+    fn start_state(&self) -> Option<usize> {
+        self.table().start_state()
+    }
+
+    fn state_ids(&self) -> BTreeSet<usize> {
+        self.table().state_ids()
+    }
+
+    fn symbols(&self) -> Vec<char> {
+        self.alphabet().to_vec()
+    }
+
+    fn is_accepting_state(&self, state: usize) -> bool {
+        self.table().is_accepting_state(state)
+    }
+
+    fn edges(&self) -> Vec<(usize, Transition, usize)> {
+        self.table()
+            .rows
+            .iter()
+            .flat_map(|r| {
+                r.transitions.iter().enumerate().filter_map(|(column, to)| {
+                    to.map(|to| (r.id, Transition::Char(self.alphabet()[column]), to))
+                })
+            })
+            .collect()
+    }
+}
+
+impl FiniteAutomaton for Automaton {
+    fn start_state(&self) -> Option<usize> {
+        match self {
+            Automaton::Nfa(nfa) => nfa.start_state(),
+            Automaton::Table(tt) => tt.start_state(),
+        }
+    }
+
+    fn state_ids(&self) -> BTreeSet<usize> {
+        match self {
+            Automaton::Nfa(nfa) => nfa.state_ids(),
+            Automaton::Table(tt) => tt.state_ids(),
+        }
+    }
+
+    fn symbols(&self) -> Vec<char> {
+        match self {
+            Automaton::Nfa(nfa) => nfa.symbols(),
+            Automaton::Table(tt) => tt.symbols(),
+        }
+    }
+
+    fn is_accepting_state(&self, state: usize) -> bool {
+        match self {
+            Automaton::Nfa(nfa) => nfa.is_accepting_state(state),
+            Automaton::Table(tt) => tt.is_accepting_state(state),
+        }
+    }
+
+    fn edges(&self) -> Vec<(usize, Transition, usize)> {
+        match self {
+            Automaton::Nfa(nfa) => nfa.edges(),
+            Automaton::Table(tt) => tt.edges(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AutomatonKind {
+    Nfa,
+    Table,
+}
+
+pub enum Automaton {
+    Nfa(NFA),
+    Table(LabeledTable),
+}
+
+impl Automaton {
+    pub fn kind(&self) -> AutomatonKind {
+        match self {
+            Automaton::Nfa(_) => AutomatonKind::Nfa,
+            Automaton::Table(_) => AutomatonKind::Table,
+        }
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            Automaton::Nfa(nfa) => nfa.is_deterministic(),
+            Automaton::Table(_) => true,
+        }
+    }
+
+    /// Whether this automaton can be converted to `kind` with [`Automaton::convert`]
+    pub fn can_convert(&self, kind: AutomatonKind) -> bool {
+        match kind {
+            AutomatonKind::Nfa => true,
+            AutomatonKind::Table => self.is_deterministic(),
+        }
+    }
+
+    pub fn memory_footprint(&self) -> usize {
+        match self {
+            Automaton::Nfa(nfa) => nfa.memory_footprint(),
+            Automaton::Table(tt) => tt.memory_footprint(),
+        }
+    }
+
+    pub fn to_nfa(&self) -> NFA {
+        match self {
+            Automaton::Nfa(nfa) => nfa.clone(),
+            Automaton::Table(tt) => NFA::from(tt),
+        }
+    }
+
+    /// Converts to the requested representation, `None` if that would require determinization
+    pub fn convert(self, kind: AutomatonKind) -> Option<Automaton> {
+        match (self, kind) {
+            (a @ Automaton::Nfa(_), AutomatonKind::Nfa) => Some(a),
+            (a @ Automaton::Table(_), AutomatonKind::Table) => Some(a),
+            (Automaton::Nfa(nfa), AutomatonKind::Table) => nfa.to_table().map(Automaton::Table),
+            (Automaton::Table(tt), AutomatonKind::Nfa) => Some(Automaton::Nfa(NFA::from(&tt))),
+        }
+    }
+}
+
+impl From<&Automaton> for Digraph {
+    fn from(value: &Automaton) -> Self {
+        Digraph::from_automaton(value, &LabelStyle::dot())
+    }
+}
+
+impl Digraph {
+    pub fn from_automaton(value: &Automaton, style: &LabelStyle) -> Self {
+        match value {
+            Automaton::Nfa(nfa) => Digraph::from_nfa(nfa, style),
+            Automaton::Table(tt) => Digraph {
+                label_style: style.clone(),
+                ..Digraph::from(tt)
+            },
+        }
+    }
+}
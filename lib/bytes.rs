@@ -0,0 +1,168 @@
+//! DFAs over bytes instead of chars, for scanning binary data that is not UTF-8
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::LabeledTable;
+
+#[derive(Debug)]
+pub enum BytesError {
+    /// Only symbols up to U+00FF stand for a byte
+    UnsupportedSymbol(char),
+}
+impl Error for BytesError {}
+impl Display for BytesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BytesError::UnsupportedSymbol(c) => {
+                write!(f, "UnsupportedSymbol: {:?} is not a byte!", c)
+            }
+        }
+    }
+}
+
+/// Marks a missing transition in [`ByteDfa`]'s flat table
+const DEAD: u32 = u32::MAX;
+
+/// A DFA reading bytes, compiled from a table whose symbols U+0000 to U+00FF each stand for the
+/// byte of the same value. States are numbered densely from 0, the start state first.
+///
+/// Transitions live in one flat table with a row of `stride` entries per state, so a step is a
+/// lookup of the byte's class followed by a single index. Bytes every state moves the same way
+/// on share a class, which keeps rows short for large alphabets.
+pub struct ByteDfa {
+    /// Class of every byte, the last class for bytes outside the alphabet, whose column is all
+    /// [`DEAD`]
+    classes: [u16; 256],
+    stride: usize,
+    /// Target of every state on every class, [`DEAD`] for a missing transition
+    transitions: Vec<u32>,
+    accepting: Vec<bool>,
+}
+
+impl ByteDfa {
+    pub fn new(table: &LabeledTable) -> Result<Self, BytesError> {
+        let rows = &table.table().rows;
+        let index = |id: usize| rows.iter().position(|r| r.id == id);
+        let column = |column: usize| -> Vec<u32> {
+            rows.iter()
+                .map(|r| {
+                    let target = r.transitions.get(column).copied().flatten();
+                    target.and_then(index).map_or(DEAD, |t| t as u32)
+                })
+                .collect()
+        };
+
+        // Identical columns share a class, numbered in order of first appearance
+        let mut ids: BTreeMap<Vec<u32>, u16> = BTreeMap::new();
+        let mut columns: Vec<Vec<u32>> = Vec::new();
+        let mut byte_classes: Vec<(u8, u16)> = Vec::new();
+        for (i, c) in table.alphabet().iter().enumerate() {
+            let byte = u8::try_from(*c).map_err(|_| BytesError::UnsupportedSymbol(*c))?;
+            let targets = column(i);
+            let class = *ids.entry(targets.clone()).or_insert_with(|| {
+                columns.push(targets);
+                columns.len() as u16 - 1
+            });
+            byte_classes.push((byte, class));
+        }
+        let mut classes = [columns.len() as u16; 256];
+        for (byte, class) in byte_classes {
+            classes[byte as usize] = class;
+        }
+
+        let stride = columns.len() + 1;
+        let mut transitions = vec![DEAD; rows.len() * stride];
+        for (class, targets) in columns.iter().enumerate() {
+            for (state, target) in targets.iter().enumerate() {
+                transitions[state * stride + class] = *target;
+            }
+        }
+        Ok(Self {
+            classes,
+            stride,
+            transitions,
+            accepting: rows.iter().map(|r| r.accepting).collect(),
+        })
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// State 0, `None` for a table without rows
+    pub fn start(&self) -> Option<usize> {
+        (!self.accepting.is_empty()).then_some(0)
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting.get(state).copied().unwrap_or(false)
+    }
+
+    /// The state `state` moves to on `byte`, `None` for a missing transition
+    pub fn step(&self, state: usize, byte: u8) -> Option<usize> {
+        let class = self.classes[byte as usize] as usize;
+        match self.transitions.get(state * self.stride + class) {
+            Some(&next) if next != DEAD => Some(next as usize),
+            _ => None,
+        }
+    }
+
+    /// Bytes in the flat table, the class map and the acceptance flags
+    pub fn memory_footprint(&self) -> usize {
+        size_of::<Self>()
+            + self.transitions.capacity() * size_of::<u32>()
+            + self.accepting.capacity() * size_of::<bool>()
+    }
+
+    pub fn accepts(&self, input: &[u8]) -> bool {
+        input
+            .iter()
+            .try_fold(self.start(), |state, byte| Some(self.step(state?, *byte)))
+            .flatten()
+            .is_some_and(|s| self.is_accepting(s))
+    }
+
+    /// Leftmost-longest non-empty match anywhere in `haystack`, like [`LabeledTable::find`]
+    pub fn find(&self, haystack: &[u8]) -> Option<RangeInclusive<usize>> {
+        self.find_iter(haystack).next()
+    }
+
+    /// Every non-overlapping match in `haystack` from left to right, see [`ByteDfa::find`]
+    pub fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+    ) -> impl Iterator<Item = RangeInclusive<usize>> + 'a {
+        let longest = move |from: usize| {
+            let mut state = self.start()?;
+            let mut end = None;
+            for (i, byte) in haystack.iter().enumerate().skip(from) {
+                match self.step(state, *byte) {
+                    Some(next) => state = next,
+                    None => break,
+                }
+                if self.is_accepting(state) {
+                    end = Some(i);
+                }
+            }
+            end
+        };
+
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            while position < haystack.len() {
+                match longest(position) {
+                    Some(end) => {
+                        let found = position..=end;
+                        position = end + 1;
+                        return Some(found);
+                    }
+                    None => position += 1,
+                }
+            }
+            None
+        })
+    }
+}
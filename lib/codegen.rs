@@ -0,0 +1,121 @@
+//! Source code generation from transition tables
+
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::LabeledTable;
+
+mod c;
+mod rust;
+
+pub use c::{CSources, c};
+pub use rust::rust;
+
+#[derive(Debug)]
+pub enum CodegenError {
+    /// The machine name is not usable as an identifier
+    InvalidName(String),
+    /// The machine name is a Rust or C keyword
+    ReservedName(String),
+    DuplicateName(String),
+    NoMachines,
+    /// The target language cannot match this symbol
+    UnsupportedSymbol(char),
+}
+impl Error for CodegenError {}
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::InvalidName(name) => {
+                write!(f, "InvalidName: {:?} is not a lowercase identifier!", name)
+            }
+            CodegenError::ReservedName(name) => {
+                write!(f, "ReservedName: {:?} is a Rust or C keyword!", name)
+            }
+            CodegenError::DuplicateName(name) => {
+                write!(f, "DuplicateName: {:?} is used twice!", name)
+            }
+            CodegenError::NoMachines => write!(f, "NoMachines: Nothing to generate!"),
+            CodegenError::UnsupportedSymbol(c) => {
+                write!(f, "UnsupportedSymbol: {:?} cannot be matched here!", c)
+            }
+        }
+    }
+}
+
+/// A table with states renumbered densely from 0, the start state first, and missing
+/// transitions dropped. Backends only need to walk this.
+pub(crate) struct FlatMachine {
+    pub name: String,
+    pub accepting: Vec<bool>,
+    /// Outgoing `(symbol, target)` pairs of each state, in alphabet order
+    pub transitions: Vec<Vec<(char, usize)>>,
+}
+
+impl FlatMachine {
+    fn new(name: &str, table: &LabeledTable) -> Self {
+        let rows = &table.table().rows;
+        let index = |id: usize| rows.iter().position(|r| r.id == id);
+        Self {
+            name: name.to_owned(),
+            accepting: rows.iter().map(|r| r.accepting).collect(),
+            transitions: rows
+                .iter()
+                .map(|r| {
+                    r.transitions
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(column, t)| Some((table.alphabet()[column], index((*t)?)?)))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Lowercase Rust keywords, reserved ones included. Names only appear with a suffix or camel
+/// cased, but `self` becomes the invalid variant `Self` and a keyword confuses either way.
+const RUST_KEYWORDS: &str = "\
+abstract as async await become box break const continue crate do dyn else enum extern \
+false final fn for gen if impl in let loop macro match mod move mut override priv pub ref \
+return self static struct super trait true try type typeof unsafe unsized use virtual \
+where while yield \
+";
+
+/// Lowercase C keywords up to C23 that are not Rust keywords too
+const C_KEYWORDS: &str = "\
+alignas alignof auto bool case char constexpr default double float goto inline int long \
+nullptr register restrict short signed sizeof static_assert switch thread_local typedef \
+typeof_unqual union unsigned void volatile \
+";
+
+/// Checks the names and flattens every machine, keeping their order as the priority order
+pub(crate) fn flatten(
+    machines: &[(&str, &LabeledTable)],
+) -> Result<Vec<FlatMachine>, CodegenError> {
+    if machines.is_empty() {
+        return Err(CodegenError::NoMachines);
+    }
+    let mut flat: Vec<FlatMachine> = Vec::new();
+    for (name, table) in machines {
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(CodegenError::InvalidName(name.to_string()));
+        }
+        if RUST_KEYWORDS
+            .split_whitespace()
+            .chain(C_KEYWORDS.split_whitespace())
+            .any(|keyword| keyword == *name)
+        {
+            return Err(CodegenError::ReservedName(name.to_string()));
+        }
+        if flat.iter().any(|m| m.name == *name) {
+            return Err(CodegenError::DuplicateName(name.to_string()));
+        }
+        flat.push(FlatMachine::new(name, table));
+    }
+    Ok(flat)
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeSet;
+
+use super::{CodegenError, FlatMachine, flatten};
+use crate::LabeledTable;
+
+/// A generated header and the source file implementing it
+pub struct CSources {
+    pub header: String,
+    pub source: String,
+}
+
+fn prototypes(machine: &FlatMachine) -> String {
+    format!(
+        "/* Length of the longest prefix of input accepted by {name}, -1 if there is none */
+long {name}_longest_match(const char *input, size_t len);
+bool {name}_accepts(const char *input, size_t len);
+",
+        name = machine.name
+    )
+}
+
+fn tables(machine: &FlatMachine, symbols: &[char]) -> String {
+    let rows: Vec<String> = machine
+        .transitions
+        .iter()
+        .map(|transitions| {
+            let cells: Vec<String> = std::iter::once("-1".to_owned())
+                .chain(symbols.iter().map(|s| {
+                    transitions
+                        .iter()
+                        .find(|(symbol, _)| symbol == s)
+                        .map_or("-1".to_owned(), |(_, target)| target.to_string())
+                }))
+                .collect();
+            format!("    {{{}}},", cells.join(", "))
+        })
+        .collect();
+    let accepting: Vec<&str> = machine
+        .accepting
+        .iter()
+        .map(|a| if *a { "true" } else { "false" })
+        .collect();
+    format!(
+        "static const int {name}_transitions[{states}][FA_CLASSES] = {{
+{rows}
+}};
+static const bool {name}_accepting[{states}] = {{{accepting}}};
+
+long {name}_longest_match(const char *input, size_t len) {{
+    return fa_run(&{name}_transitions[0][0], {name}_accepting, input, len);
+}}
+
+bool {name}_accepts(const char *input, size_t len) {{
+    return {name}_longest_match(input, len) == (long)len;
+}}
+",
+        name = machine.name,
+        states = machine.accepting.len(),
+        rows = rows.join("\n"),
+        accepting = accepting.join(", "),
+    )
+}
+
+/// Generates a table-driven C scanner, `header_name` is what the source `#include`s.
+///
+/// Every machine gets `<name>_longest_match` and `<name>_accepts` over ASCII input, and
+/// `fa_next_token` returns the `FA_TOKEN_<NAME>` with the longest match, ties going to the
+/// machine listed first.
+pub fn c(machines: &[(&str, &LabeledTable)], header_name: &str) -> Result<CSources, CodegenError> {
+    let machines = flatten(machines)?;
+    let symbols: Vec<char> = machines
+        .iter()
+        .flat_map(|m| m.transitions.iter().flatten().map(|(symbol, _)| *symbol))
+        .collect::<BTreeSet<char>>()
+        .into_iter()
+        .collect();
+    if let Some(symbol) = symbols.iter().find(|s| !s.is_ascii()) {
+        return Err(CodegenError::UnsupportedSymbol(*symbol));
+    }
+
+    let guard = format!(
+        "FA_VIZ_{}_H",
+        header_name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect::<String>()
+    );
+    let mut header = format!(
+        "/* Generated by FA-viz, do not edit. */\n#ifndef {guard}\n#define {guard}\n\n#include <stdbool.h>\n#include <stddef.h>\n\ntypedef enum {{\n"
+    );
+    for machine in &machines {
+        header += &format!("    FA_TOKEN_{},\n", machine.name.to_ascii_uppercase());
+    }
+    header += "} fa_token;\n\n";
+    for machine in &machines {
+        header += &prototypes(machine);
+    }
+    header +=
+        "/* Token at the start of input with its length in *match_len, -1 if there is none */\n";
+    header += "int fa_next_token(const char *input, size_t len, size_t *match_len);\n\n#endif\n";
+
+    // Column 0 is every byte outside the alphabet, which always leads nowhere
+    let mut classes = vec![0; 128];
+    for (i, symbol) in symbols.iter().enumerate() {
+        classes[*symbol as usize] = i + 1;
+    }
+    let classes: Vec<String> = classes
+        .chunks(16)
+        .map(|row| {
+            let row: Vec<String> = row.iter().map(usize::to_string).collect();
+            format!("\n    {},", row.join(", "))
+        })
+        .collect();
+    let mut source = format!(
+        "/* Generated by FA-viz, do not edit. */\n#include \"{header_name}\"\n\n#define FA_CLASSES {count}\n\nstatic const unsigned char fa_classes[128] = {{{classes}\n}};\n\n",
+        count = symbols.len() + 1,
+        classes = classes.concat(),
+    );
+    source += "static long fa_run(const int *transitions, const bool *accepting, const char *input,
+                   size_t len) {
+    int state = 0;
+    long last = accepting[0] ? 0 : -1;
+    for (size_t i = 0; i < len; i++) {
+        unsigned char c = (unsigned char)input[i];
+        state = c < 128 ? transitions[state * FA_CLASSES + fa_classes[c]] : -1;
+        if (state < 0) {
+            break;
+        }
+        if (accepting[state]) {
+            last = (long)(i + 1);
+        }
+    }
+    return last;
+}
+
+";
+    for machine in &machines {
+        source += &match machine.accepting.is_empty() {
+            true => format!(
+                "long {name}_longest_match(const char *input, size_t len) {{\n    (void)input;\n    (void)len;\n    return -1;\n}}\n\nbool {name}_accepts(const char *input, size_t len) {{\n    return {name}_longest_match(input, len) == (long)len;\n}}\n",
+                name = machine.name
+            ),
+            false => tables(machine, &symbols),
+        };
+        source += "\n";
+    }
+    source += "int fa_next_token(const char *input, size_t len, size_t *match_len) {\n";
+    source += "    int best = -1;\n    long best_len = -1;\n    long l;\n";
+    for machine in &machines {
+        source += &format!(
+            "    if ((l = {}_longest_match(input, len)) > best_len) {{\n        best = FA_TOKEN_{};\n        best_len = l;\n    }}\n",
+            machine.name,
+            machine.name.to_ascii_uppercase()
+        );
+    }
+    source += "    if (best >= 0) {\n        *match_len = (size_t)best_len;\n    }\n    return best;\n}\n";
+
+    Ok(CSources { header, source })
+}
@@ -0,0 +1,96 @@
+use super::{CodegenError, FlatMachine, flatten};
+use crate::LabeledTable;
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn matcher(machine: &FlatMachine) -> String {
+    let accepting: Vec<String> = (0..machine.accepting.len())
+        .filter(|s| machine.accepting[*s])
+        .map(|s| s.to_string())
+        .collect();
+    let is_accepting = match accepting.is_empty() {
+        true => "false".to_owned(),
+        false => format!("matches!(state, {})", accepting.join(" | ")),
+    };
+
+    let mut arms = String::new();
+    for (state, transitions) in machine.transitions.iter().enumerate() {
+        if transitions.is_empty() {
+            continue;
+        }
+        arms += &format!("            {} => match c {{\n", state);
+        for (symbol, target) in transitions {
+            arms += &format!("                {:?} => {},\n", symbol, target);
+        }
+        arms += "                _ => return last,\n            },\n";
+    }
+
+    format!(
+        "/// Length in bytes of the longest prefix of `input` accepted by `{name}`
+pub fn {name}_longest_match(input: &str) -> Option<usize> {{
+    let mut state: usize = 0;
+    let mut last = if {is_accepting} {{ Some(0) }} else {{ None }};
+    for (i, c) in input.char_indices() {{
+        state = match state {{
+{arms}            _ => return last,
+        }};
+        if {is_accepting} {{
+            last = Some(i + c.len_utf8());
+        }}
+    }}
+    last
+}}
+
+pub fn {name}_accepts(input: &str) -> bool {{
+    {name}_longest_match(input) == Some(input.len())
+}}
+",
+        name = machine.name,
+        is_accepting = is_accepting,
+        arms = arms,
+    )
+}
+
+/// Generates a standalone Rust source file with a recognizer per machine and a `next_token`
+/// lexer over all of them. Longer matches win, ties go to the machine listed first.
+///
+/// Every machine gets `<name>_longest_match` and `<name>_accepts`, `Token` has one variant per
+/// machine in CamelCase. The output only uses `core`, so it can be `include!`d from `build.rs`
+/// output.
+pub fn rust(machines: &[(&str, &LabeledTable)]) -> Result<String, CodegenError> {
+    let machines = flatten(machines)?;
+
+    let mut out = String::from("// Generated by FA-viz, do not edit.\n\n");
+    out += "#[derive(Debug, Copy, Clone, PartialEq, Eq)]\npub enum Token {\n";
+    for machine in &machines {
+        out += &format!("    {},\n", camel_case(&machine.name));
+    }
+    out += "}\n\n";
+    for machine in &machines {
+        out += &matcher(machine);
+        out += "\n";
+    }
+
+    out += "/// The token at the start of `input` and its length in bytes, longest match first\n";
+    out += "pub fn next_token(input: &str) -> Option<(Token, usize)> {\n";
+    out += "    let mut best: Option<(Token, usize)> = None;\n";
+    for machine in &machines {
+        out += &format!(
+            "    if let Some(len) = {}_longest_match(input) {{\n        if best.map_or(true, |(_, best)| len > best) {{\n            best = Some((Token::{}, len));\n        }}\n    }}\n",
+            machine.name,
+            camel_case(&machine.name)
+        );
+    }
+    out += "    best\n}\n";
+    Ok(out)
+}
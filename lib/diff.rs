@@ -0,0 +1,330 @@
+//! Comparison of two deterministic automata by walking their product
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Display;
+
+use transition_tables::{TransitionTable, TransitionTableRow};
+
+use crate::{
+    Digraph, LabelStyle, LabeledTable, TransitionTableExt, digraph::escaped, nfa::Transition,
+};
+
+type State = usize;
+
+/// A state of the product, `None` on the side whose automaton has already rejected
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pair {
+    pub first: Option<State>,
+    pub second: Option<State>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Only the second automaton has the transition
+    Added,
+    /// Only the first automaton has the transition
+    Removed,
+    /// Both have it, but the targets are not each other's counterparts
+    Changed,
+}
+
+impl Change {
+    fn name(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Change::Added => "green",
+            Change::Removed => "red",
+            Change::Changed => "orange",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProductEdge {
+    /// Index into [`Diff::pairs`]
+    pub from: usize,
+    pub symbol: char,
+    /// Index into [`Diff::pairs`]
+    pub to: usize,
+    pub change: Option<Change>,
+}
+
+pub struct Diff {
+    /// Shortest string accepted by exactly one of the automata, `None` if they are equivalent
+    pub distinguishing: Option<String>,
+    /// States of the first automaton that never pair up with a state of the second, by their
+    /// ids after [`TransitionTableExt::canonicalize`] so the numbering of the input does not matter
+    pub only_in_first: Vec<State>,
+    /// States of the second automaton that never pair up with a state of the first, renumbered
+    /// the same way
+    pub only_in_second: Vec<State>,
+    /// Reachable product states in BFS order from the pair of start states
+    pub pairs: Vec<Pair>,
+    pub edges: Vec<ProductEdge>,
+    names: BTreeMap<usize, String>,
+    accepting: Vec<(bool, bool)>,
+}
+
+struct Side<'a> {
+    table: &'a LabeledTable,
+    rows: BTreeMap<State, (bool, &'a [Option<State>])>,
+    /// Canonical id of every state
+    canonical: BTreeMap<State, State>,
+}
+
+impl<'a> Side<'a> {
+    fn new(table: &'a LabeledTable) -> Self {
+        let rows = table
+            .table()
+            .rows
+            .iter()
+            .map(|r| (r.id, (r.accepting, r.transitions.as_slice())))
+            .collect();
+        let mut copy = TransitionTable {
+            rows: table
+                .table()
+                .rows
+                .iter()
+                .map(|r| TransitionTableRow {
+                    id: r.id,
+                    accepting: r.accepting,
+                    transitions: r.transitions.clone(),
+                })
+                .collect(),
+        };
+        let canonical = copy.canonicalize();
+        Self {
+            table,
+            rows,
+            canonical,
+        }
+    }
+
+    fn start(&self) -> Option<State> {
+        self.table.table().rows.first().map(|r| r.id)
+    }
+
+    fn accepting(&self, state: Option<State>) -> bool {
+        state.is_some_and(|s| self.rows.get(&s).is_some_and(|r| r.0))
+    }
+
+    fn next(&self, state: Option<State>, c: char) -> Option<State> {
+        let column = self.table.alphabet().iter().position(|a| *a == c)?;
+        self.rows.get(&state?)?.1.get(column).copied().flatten()
+    }
+
+    /// The state's label, or its canonical id
+    fn name(&self, state: Option<State>) -> String {
+        state.map_or("∅".to_owned(), |s| {
+            match self.table.state_labels.get(&s) {
+                Some(label) => label.clone(),
+                None => self.canonical[&s].to_string(),
+            }
+        })
+    }
+}
+
+impl Diff {
+    pub fn is_equivalent(&self) -> bool {
+        self.distinguishing.is_none()
+    }
+
+    /// The product automaton, with the transitions that differ colored green when added, red
+    /// when removed and orange when changed, and states accepted by only one side in red
+    pub fn to_digraph(&self) -> Digraph {
+        let mut graph = Digraph {
+            start: (!self.pairs.is_empty()).then_some(0),
+            labels: escaped(&self.names),
+            ..Default::default()
+        };
+        for (i, accepting) in self.accepting.iter().enumerate() {
+            match accepting {
+                (true, true) => {
+                    graph.accepting_nodes.insert(i);
+                }
+                (true, false) | (false, true) => {
+                    graph.node_colors.insert(i, "red".to_owned());
+                }
+                (false, false) => {}
+            }
+        }
+        for edge in &self.edges {
+            let key = (edge.from, BTreeSet::from([edge.to]));
+            let symbol = Transition::Char(edge.symbol).to_string();
+            graph
+                .edges
+                .entry(key.clone())
+                .and_modify(|acc| {
+                    acc.push('|');
+                    acc.push_str(&symbol);
+                })
+                .or_insert(symbol);
+            if let Some(change) = edge.change {
+                graph.edge_colors.insert(key, change.color().to_owned());
+            }
+        }
+        graph
+    }
+}
+
+/// Compares the languages and structure of two DFAs. The start states are paired up and every
+/// pair of states reached on the same string after that, so the first pair found in BFS order
+/// whose acceptance differs gives the shortest distinguishing string. Symbols outside an
+/// automaton's alphabet lead to its dead state.
+pub fn diff(first: &LabeledTable, second: &LabeledTable) -> Diff {
+    let (a, b) = (Side::new(first), Side::new(second));
+    let mut alphabet: Vec<char> = first.alphabet().to_vec();
+    for c in second.alphabet() {
+        if !alphabet.contains(c) {
+            alphabet.push(*c);
+        }
+    }
+
+    let mut index: BTreeMap<Pair, usize> = BTreeMap::new();
+    let mut pairs: Vec<Pair> = Vec::new();
+    let mut parents: Vec<Option<(usize, char)>> = Vec::new();
+    let mut product: Vec<(usize, char, usize)> = Vec::new();
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    let start = Pair {
+        first: a.start(),
+        second: b.start(),
+    };
+    if start.first.is_some() || start.second.is_some() {
+        index.insert(start, 0);
+        pairs.push(start);
+        parents.push(None);
+        queue.push_back(0);
+    }
+    while let Some(i) = queue.pop_front() {
+        let pair = pairs[i];
+        for c in &alphabet {
+            let next = Pair {
+                first: a.next(pair.first, *c),
+                second: b.next(pair.second, *c),
+            };
+            if next.first.is_none() && next.second.is_none() {
+                continue;
+            }
+            let j = *index.entry(next).or_insert_with(|| {
+                pairs.push(next);
+                parents.push(Some((i, *c)));
+                queue.push_back(pairs.len() - 1);
+                pairs.len() - 1
+            });
+            product.push((i, *c, j));
+        }
+    }
+
+    let accepting: Vec<(bool, bool)> = pairs
+        .iter()
+        .map(|p| (a.accepting(p.first), b.accepting(p.second)))
+        .collect();
+    let distinguishing = accepting.iter().position(|(x, y)| x != y).map(|mut i| {
+        let mut word = Vec::new();
+        while let Some((parent, c)) = parents[i] {
+            word.push(c);
+            i = parent;
+        }
+        word.into_iter().rev().collect()
+    });
+
+    // The counterpart of a state is the first state it was paired with
+    let mut mates: (BTreeMap<State, State>, BTreeMap<State, State>) = Default::default();
+    for pair in &pairs {
+        if let Pair {
+            first: Some(x),
+            second: Some(y),
+        } = *pair
+        {
+            mates.0.entry(x).or_insert(y);
+            mates.1.entry(y).or_insert(x);
+        }
+    }
+    let edges = product
+        .into_iter()
+        .map(|(from, symbol, to)| {
+            let change = match (pairs[to].first, pairs[to].second) {
+                (Some(x), Some(y))
+                    if mates.0.get(&x) != Some(&y) || mates.1.get(&y) != Some(&x) =>
+                {
+                    Some(Change::Changed)
+                }
+                (Some(_), Some(_)) => None,
+                (Some(_), None) => Some(Change::Removed),
+                (None, _) => Some(Change::Added),
+            };
+            ProductEdge {
+                from,
+                symbol,
+                to,
+                change,
+            }
+        })
+        .collect();
+
+    let only = |side: &Side, mates: &BTreeMap<State, State>| -> Vec<State> {
+        let mut states: Vec<State> = side
+            .rows
+            .keys()
+            .filter(|s| !mates.contains_key(s))
+            .map(|s| side.canonical[s])
+            .collect();
+        states.sort_unstable();
+        states
+    };
+    let names = pairs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i, format!("{},{}", a.name(p.first), b.name(p.second))))
+        .collect();
+    Diff {
+        distinguishing,
+        only_in_first: only(&a, &mates.0),
+        only_in_second: only(&b, &mates.1),
+        pairs,
+        edges,
+        names,
+        accepting,
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |states: &[State]| match states.is_empty() {
+            true => "none".to_owned(),
+            false => states
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        writeln!(f, "equivalent: {}", self.is_equivalent())?;
+        if let Some(word) = &self.distinguishing {
+            writeln!(f, "distinguishing: {:?}", word)?;
+        }
+        writeln!(f, "only in first: {}", list(&self.only_in_first))?;
+        write!(f, "only in second: {}", list(&self.only_in_second))?;
+        for (edge, change) in self
+            .edges
+            .iter()
+            .filter_map(|e| e.change.map(|change| (e, change)))
+        {
+            write!(
+                f,
+                "\n{}: {} -{}-> {}",
+                change.name(),
+                self.names[&edge.from],
+                Transition::Char(edge.symbol).render(&LabelStyle::text()),
+                self.names[&edge.to]
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -6,20 +6,173 @@ use std::{
 };
 use transition_tables::TransitionTable;
 
-use crate::NFA;
+use crate::{
+    LabelStyle, LabeledTable, NFA,
+    nfa::Transition,
+    table::column_symbol,
+    tokens::{self, Tokens},
+};
 
 type Node = usize;
+
+/// Escapes text for a Graphviz HTML-like label
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[derive(Default)]
 pub struct Digraph {
     pub edges: BTreeMap<(Node, BTreeSet<Node>), String>,
     pub accepting_nodes: BTreeSet<Node>,
+    pub start: Option<Node>,
+    /// Unreachable or dead nodes, rendered grayed out
+    pub useless_nodes: BTreeSet<Node>,
+    /// Display names replacing the numeric ids, in HTML-like label markup like every label
+    pub labels: BTreeMap<Node, String>,
+    /// Graphviz colors of highlighted nodes
+    pub node_colors: BTreeMap<Node, String>,
+    /// Graphviz colors of highlighted edges, keyed like [`Digraph::edges`]
+    pub edge_colors: BTreeMap<(Node, BTreeSet<Node>), String>,
+    /// Labeled groups of nodes, drawn boxed in together
+    pub clusters: Vec<(String, BTreeSet<Node>)>,
+    /// Labeled edges between whole clusters, keyed by their index in [`Digraph::clusters`]
+    pub cluster_edges: BTreeMap<(usize, usize), String>,
+    /// How lambda is spelled in the labels
+    pub label_style: LabelStyle,
+}
+
+/// `transition` as a piece of an edge label, tokens by name. Plain text writes `|` and `,` as
+/// Mermaid character references since they separate the pieces, HTML-like labels get them from
+/// [`Transition::render`]. Inside a token both are written as character references.
+fn edge_piece(transition: &Transition, tokens: &Tokens, style: &LabelStyle) -> String {
+    let reference = |c: char| match style.html {
+        true => format!("&#{};", c as u32),
+        false => format!("#{};", c as u32),
+    };
+    match transition {
+        Transition::Char(c @ ('|' | ',')) if !style.html => reference(*c),
+        Transition::Char(c) if tokens.contains_key(c) => tokens::render(tokens, transition, style)
+            .chars()
+            .map(|c| match c {
+                '|' | ',' => reference(c),
+                c => c.to_string(),
+            })
+            .collect(),
+        _ => transition.render(style),
+    }
+}
+
+/// Escapes every label, see [`escape`]
+pub(crate) fn escaped(labels: &BTreeMap<Node, String>) -> BTreeMap<Node, String> {
+    labels.iter().map(|(n, l)| (*n, escape(l))).collect()
+}
+
+impl Digraph {
+    pub fn nodes(&self) -> BTreeSet<Node> {
+        self.edges
+            .keys()
+            .flat_map(|(src, destinations)| {
+                std::iter::once(*src).chain(destinations.iter().copied())
+            })
+            .chain(self.accepting_nodes.iter().copied())
+            .chain(self.start)
+            .collect()
+    }
+
+    fn closure(&self, from: impl IntoIterator<Item = Node>, forward: bool) -> BTreeSet<Node> {
+        let mut seen = BTreeSet::new();
+        let mut stack: Vec<Node> = from.into_iter().collect();
+        while let Some(n) = stack.pop() {
+            if !seen.insert(n) {
+                continue;
+            }
+            for (src, destinations) in self.edges.keys() {
+                if forward && *src == n {
+                    stack.extend(destinations.iter().copied());
+                } else if !forward && destinations.contains(&n) {
+                    stack.push(*src);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Mark states that are unreachable from the start or cannot reach an accepting state
+    pub fn mark_useless(mut self) -> Self {
+        let reachable = self.closure(self.start, true);
+        let can_accept = self.closure(self.accepting_nodes.iter().copied(), false);
+        self.useless_nodes = self
+            .nodes()
+            .into_iter()
+            .filter(|n| !reachable.contains(n) || !can_accept.contains(n))
+            .collect();
+        self
+    }
+
+    /// Drop a node and every edge into or out of it
+    pub fn hide_node(mut self, node: Node) -> Self {
+        let mut edges: BTreeMap<(Node, BTreeSet<Node>), String> = BTreeMap::new();
+        for ((src, mut destinations), label) in std::mem::take(&mut self.edges) {
+            destinations.remove(&node);
+            if src == node || destinations.is_empty() {
+                continue;
+            }
+            edges
+                .entry((src, destinations))
+                .and_modify(|acc| {
+                    acc.push('|');
+                    acc.push_str(&label);
+                })
+                .or_insert(label);
+        }
+        self.edges = edges;
+        self.accepting_nodes.remove(&node);
+        self.useless_nodes.remove(&node);
+        self.labels.remove(&node);
+        self.node_colors.remove(&node);
+        for (_, nodes) in &mut self.clusters {
+            nodes.remove(&node);
+        }
+        self
+    }
+
+    /// Label nodes with the words from [`NFA::state_names`]
+    pub fn with_state_names(mut self, names: &BTreeMap<Node, String>) -> Self {
+        self.labels = names
+            .iter()
+            .map(|(n, word)| {
+                let label = match word.is_empty() {
+                    true => Transition::Lambda.render(&self.label_style),
+                    false => word
+                        .chars()
+                        .map(|c| Transition::Char(c).to_string())
+                        .collect(),
+                };
+                (*n, label)
+            })
+            .collect();
+        self
+    }
 }
 
 impl From<&NFA> for Digraph {
     fn from(value: &NFA) -> Self {
-        let mut graph = Self::default();
+        Digraph::from_nfa(value, &LabelStyle::dot())
+    }
+}
 
+impl Digraph {
+    pub fn from_nfa(value: &NFA, style: &LabelStyle) -> Self {
         let states = value.states();
+        let mut graph = Self {
+            start: states.keys().next().copied(),
+            labels: escaped(value.labels()),
+            label_style: style.clone(),
+            ..Default::default()
+        };
         for (state, (accepting, transitions)) in states {
             for (transition, targets) in transitions {
                 graph
@@ -27,9 +180,9 @@ impl From<&NFA> for Digraph {
                     .entry((state, targets))
                     .and_modify(|acc| {
                         acc.push('|');
-                        acc.push_str(&transition.to_string());
+                        acc.push_str(&edge_piece(&transition, value.tokens(), style));
                     })
-                    .or_insert(transition.to_string());
+                    .or_insert(edge_piece(&transition, value.tokens(), style));
             }
             if accepting {
                 graph.accepting_nodes.insert(state);
@@ -42,14 +195,40 @@ impl From<&NFA> for Digraph {
 
 impl From<&TransitionTable> for Digraph {
     fn from(value: &TransitionTable) -> Self {
-        let mut graph = Self::default();
+        Digraph::from_table(value, |_, i| Transition::Char(column_symbol(i)).to_string())
+    }
+}
+
+impl From<&LabeledTable> for Digraph {
+    fn from(value: &LabeledTable) -> Self {
+        let mut graph = Digraph::from_table(value.table(), |id, i| value.edge_label(id, i));
+        graph.labels = value
+            .state_labels
+            .keys()
+            .chain(value.state_outputs.keys())
+            .map(|id| match value.state_outputs.get(id) {
+                Some(output) => {
+                    let label = format!("{}/{}", value.state_name(*id), output);
+                    (*id, escape(&label))
+                }
+                None => (*id, escape(&value.state_name(*id))),
+            })
+            .collect();
+        graph
+    }
+}
+
+impl Digraph {
+    fn from_table(value: &TransitionTable, label: impl Fn(Node, usize) -> String) -> Self {
+        let mut graph = Self {
+            start: value.rows.first().map(|r| r.id),
+            ..Default::default()
+        };
 
         for state in &value.rows {
             for (i, t) in state.transitions.iter().enumerate() {
                 if let Some(t) = t {
-                    let transition: String = char::from_u32(i as u32 + 'a' as u32)
-                        .expect("Unable to convert from decimal to char.")
-                        .to_string();
+                    let transition: String = label(state.id, i);
                     let destination: Node = *t;
                     graph
                         .edges
@@ -70,40 +249,159 @@ impl From<&TransitionTable> for Digraph {
     }
 }
 
-impl Display for Digraph {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Digraph {
+    /// Node, cluster and edge statements. Nodes are named `prefix` followed by their id, and
+    /// with a prefix every node is defined and labeled with its id unless it has a label.
+    fn statements(&self, prefix: &str) -> String {
+        const USELESS_STYLE: &str = "style=dashed color=gray fontcolor=gray";
+
+        let mut nodes: BTreeSet<&Node> = self
+            .accepting_nodes
+            .iter()
+            .chain(&self.useless_nodes)
+            .chain(self.labels.keys())
+            .chain(self.node_colors.keys())
+            .collect();
+        let all = self.nodes();
+        if !prefix.is_empty() {
+            nodes.extend(&all);
+        }
         let node_defs: String = Itertools::intersperse(
-            self.accepting_nodes
-                .iter()
-                .map(|n| format!("{} [shape=doublecircle]", n)),
+            nodes.into_iter().map(|n| {
+                let mut attrs = Vec::new();
+                if self.accepting_nodes.contains(n) {
+                    attrs.push("shape=doublecircle".to_owned());
+                }
+                if self.useless_nodes.contains(n) {
+                    attrs.push(USELESS_STYLE.to_owned());
+                }
+                if let Some(label) = self.labels.get(n) {
+                    attrs.push(format!("label=<{}>", label));
+                } else if !prefix.is_empty() {
+                    attrs.push(format!("label=<{}>", n));
+                }
+                if let Some(color) = self.node_colors.get(n) {
+                    attrs.push(format!("color={} fontcolor={}", color, color));
+                }
+                format!("{}{} [{}]", prefix, n, attrs.join(" "))
+            }),
             "\n".to_owned(),
         )
         .collect();
         let edge_defs: String = Itertools::intersperse(
             self.edges.iter().map(|((src, destinations), label)| {
+                let style = if self.useless_nodes.contains(src)
+                    || destinations.iter().all(|d| self.useless_nodes.contains(d))
+                {
+                    format!(" {}", USELESS_STYLE)
+                } else if let Some(color) = self.edge_colors.get(&(*src, destinations.clone())) {
+                    format!(" color={} fontcolor={}", color, color)
+                } else {
+                    String::new()
+                };
                 format!(
-                    "{} -> {{ {} }} [label=<{}>]",
-                    src.to_string(),
+                    "{}{} -> {{ {} }} [label=<{}>{}]",
+                    prefix,
+                    src,
                     destinations
                         .iter()
-                        .map(|n| n.to_string())
+                        .map(|n| format!("{}{}", prefix, n))
                         .reduce(|acc, n| acc + "," + &n)
                         .unwrap(),
                     label,
+                    style,
                 )
             }),
             "\n".to_owned(),
         )
         .collect();
+        let cluster_defs: String = self
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(i, (label, nodes))| {
+                let nodes: String = nodes.iter().map(|n| format!("{}{}; ", prefix, n)).collect();
+                format!(
+                    "subgraph cluster_{}{} {{ label=<{}>; {}}}\n",
+                    prefix, i, label, nodes
+                )
+            })
+            .collect();
+        let cluster_edge_defs: String = self
+            .cluster_edges
+            .iter()
+            .filter_map(|((src, dst), label)| {
+                // Edges attach to the first node of a cluster and are clipped at its box, except
+                // loops which Graphviz can't clip
+                let anchor = |i: usize| self.clusters.get(i)?.1.first().copied();
+                let clip = match src == dst {
+                    true => String::new(),
+                    false => format!(
+                        " ltail=cluster_{}{} lhead=cluster_{}{}",
+                        prefix, src, prefix, dst
+                    ),
+                };
+                Some(format!(
+                    "{}{} -> {}{} [label=<{}>{}]\n",
+                    prefix,
+                    anchor(*src)?,
+                    prefix,
+                    anchor(*dst)?,
+                    label,
+                    clip
+                ))
+            })
+            .collect();
+        format!(
+            "{}\n{}{}{}",
+            node_defs, cluster_defs, cluster_edge_defs, edge_defs
+        )
+    }
+
+    /// One document drawing every graph boxed in on its own, labeled with its name. Nodes of the
+    /// `i`th graph are named `g<i>_<id>` so ids can repeat between graphs.
+    pub fn combined(graphs: &[(String, Digraph)]) -> String {
+        let subgraphs: String = graphs
+            .iter()
+            .enumerate()
+            .map(|(i, (name, graph))| {
+                format!(
+                    "subgraph cluster_g{} {{\nlabel=<{}>;\n{}\n}}\n",
+                    i,
+                    escape(name),
+                    graph.statements(&format!("g{}_", i))
+                )
+            })
+            .collect();
+        let compound = graphs.iter().any(|(_, g)| !g.cluster_edges.is_empty());
+        format!(
+            "digraph {{
+newrank=true;
+rankdir=LR;
+{}{}}}",
+            match compound {
+                true => "compound=true;\n",
+                false => "",
+            },
+            subgraphs
+        )
+    }
+}
+
+impl Display for Digraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "digraph {{
 newrank=true;
 rankdir=LR;
-{}
-{}
+{}{}
 }}",
-            node_defs, edge_defs
+            match self.cluster_edges.is_empty() {
+                true => "",
+                false => "compound=true;\n",
+            },
+            self.statements("")
         )
     }
 }
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+use crate::LabeledTable;
+
+/// Previous node of the cheapest way to every node and the symbol its move wrote, `None` for a
+/// deleted input symbol
+type Parents = Vec<Option<(usize, Option<char>)>>;
+
+impl LabeledTable {
+    /// Fewest inserted, deleted or replaced symbols that make the table accept `input`, `None`
+    /// if it accepts nothing
+    pub fn min_edit_distance(&self, input: &str) -> Option<usize> {
+        self.closest_accepted(input).map(|(_, distance)| distance)
+    }
+
+    /// An accepted string as few edits away from `input` as possible and that number of edits,
+    /// for "did you mean" suggestions. Searches the product of input positions and states
+    /// breadth first by cost. `None` if the table accepts nothing.
+    pub fn closest_accepted(&self, input: &str) -> Option<(String, usize)> {
+        let input: Vec<char> = input.chars().collect();
+        let rows = &self.table().rows;
+        let targets = self.target_rows();
+        if rows.is_empty() {
+            return None;
+        }
+
+        // Node `position * rows + row` is being in `row` after reading `position` symbols
+        let node = |position: usize, row: usize| position * rows.len() + row;
+        let nodes = (input.len() + 1) * rows.len();
+        let mut cost = vec![usize::MAX; nodes];
+        let mut parent: Parents = vec![None; nodes];
+        let mut done = vec![false; nodes];
+        let mut queue = VecDeque::from([(0, 0)]);
+        cost[0] = 0;
+        while let Some((position, row)) = queue.pop_front() {
+            let current = node(position, row);
+            if std::mem::replace(&mut done[current], true) {
+                continue;
+            }
+            if position == input.len() && rows[row].accepting {
+                return Some((self.edited_word(&parent, current), cost[current]));
+            }
+
+            // Reading the next symbol is free, replacing it, deleting it or inserting a symbol
+            // before it costs one edit
+            let next = input.get(position);
+            let mut moves = Vec::new();
+            if next.is_some() {
+                moves.push((position + 1, row, 1, None));
+            }
+            for (column, target) in targets[row].iter().enumerate() {
+                let Some(target) = *target else {
+                    continue;
+                };
+                let symbol = self.alphabet()[column];
+                if let Some(c) = next {
+                    let step = usize::from(*c != symbol);
+                    moves.push((position + 1, target, step, Some(symbol)));
+                }
+                moves.push((position, target, 1, Some(symbol)));
+            }
+
+            // 0-1 breadth first search, free moves go to the front of the queue
+            for (position, target, step, symbol) in moves {
+                let to = node(position, target);
+                if cost[current] + step < cost[to] {
+                    cost[to] = cost[current] + step;
+                    parent[to] = Some((current, symbol));
+                    match step {
+                        0 => queue.push_front((position, target)),
+                        _ => queue.push_back((position, target)),
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The candidate string spelled by the moves leading to `node`
+    fn edited_word(&self, parent: &Parents, node: usize) -> String {
+        let mut symbols = Vec::new();
+        let mut current = node;
+        while let Some((previous, symbol)) = parent[current] {
+            symbols.extend(symbol);
+            current = previous;
+        }
+        symbols.into_iter().rev().collect()
+    }
+}
@@ -0,0 +1,236 @@
+//! Import of Graphviz DOT digraphs, including the ones [`crate::Digraph`] renders
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::{
+    NFA,
+    import::{assign_ids, labels, parse_label},
+    nfa::{State, Transition, Transitions},
+};
+
+#[derive(Debug)]
+pub enum DotError {
+    MissingHeader,
+    InvalidLabel(String),
+    MissingLabel(String, String),
+    NoStates,
+}
+impl Error for DotError {}
+impl Display for DotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DotError::MissingHeader => write!(f, "MissingHeader: Expected digraph {{ ... }}!"),
+            DotError::InvalidLabel(l) => {
+                write!(f, "InvalidLabel: Unable to parse transition label {:?}!", l)
+            }
+            DotError::MissingLabel(from, to) => {
+                write!(f, "MissingLabel: Edge {} -> {} has no label!", from, to)
+            }
+            DotError::NoStates => write!(f, "NoStates: Graph has no nodes!"),
+        }
+    }
+}
+
+pub fn is_dot(s: &str) -> bool {
+    s.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("//") && !l.starts_with('#'))
+        .is_some_and(|l| l.starts_with("digraph") || l.starts_with("strict digraph"))
+}
+
+/// Splits the graph body into statements on `;` and newlines outside of quotes, `<...>` and `[...]`
+fn statements(body: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let (mut quoted, mut depth) = (false, 0usize);
+    for c in body.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' | '[' if !quoted => depth += 1,
+            '>' | ']' if !quoted => depth = depth.saturating_sub(1),
+            ';' | '\n' if !quoted && depth == 0 => {
+                statements.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    statements.push(current);
+    statements
+        .into_iter()
+        .map(|s| s.trim().to_owned())
+        .filter(|s| !s.is_empty() && !s.starts_with("//") && !s.starts_with('#'))
+        .collect()
+}
+
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| s.strip_prefix('<').and_then(|s| s.strip_suffix('>')))
+        .unwrap_or(s)
+        .to_owned()
+}
+
+/// Splits `target [key=value ...]` into the target and its attributes
+fn attributes(statement: &str) -> (&str, BTreeMap<String, String>) {
+    let Some((target, list)) = statement.split_once('[') else {
+        return (statement.trim(), BTreeMap::new());
+    };
+    let list = list.trim_end().trim_end_matches(']');
+    let (mut attrs, mut key, mut value) = (BTreeMap::new(), String::new(), None::<String>);
+    let (mut quoted, mut depth) = (false, 0usize);
+    for c in list.chars().chain([',']) {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => depth += 1,
+            '>' if !quoted => depth = depth.saturating_sub(1),
+            '=' if !quoted && depth == 0 && value.is_none() => {
+                value = Some(String::new());
+                continue;
+            }
+            // Whitespace only separates pairs once a value has started, `label = "a"` is one pair
+            ' ' | '\t' if !quoted && depth == 0 && value.as_ref().is_none_or(String::is_empty) => {
+                continue;
+            }
+            ',' | ' ' | '\t' if !quoted && depth == 0 => {
+                if let Some(value) = value.take() {
+                    attrs.insert(key.trim().to_owned(), unquote(&value));
+                }
+                key.clear();
+                continue;
+            }
+            _ => {}
+        }
+        match &mut value {
+            Some(value) => value.push(c),
+            None => key.push(c),
+        }
+    }
+    (target.trim(), attrs)
+}
+
+fn node_ids(s: &str) -> Vec<String> {
+    let s = s.trim();
+    match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        Some(group) => group
+            .split([',', ';', ' '])
+            .filter(|n| !n.trim().is_empty())
+            .map(unquote)
+            .collect(),
+        None => vec![unquote(s)],
+    }
+}
+
+/// Parses `a -> b [label=...]` edges, including `a -> { b c }` groups, and `shape=doublecircle`
+/// accepting nodes. The start state is the target of an edge from a start marker, a node without
+/// incoming edges drawn invisibly (`point` or `none` shaped, `invis` styled or with an empty
+/// label), and otherwise the source of the first edge.
+/// `node [...]` defaults apply to nodes mentioned after them, other attributes are ignored.
+/// State ids are assigned by [`assign_ids`], other names are kept as labels.
+pub fn parse(s: &str) -> Result<NFA, DotError> {
+    if !is_dot(s) {
+        return Err(DotError::MissingHeader);
+    }
+    let body = s
+        .split_once('{')
+        .and_then(|(_, body)| body.rsplit_once('}'))
+        .ok_or(DotError::MissingHeader)?
+        .0;
+
+    let mut names: Vec<String> = Vec::new();
+    let mut accepting: BTreeSet<String> = BTreeSet::new();
+    let mut markers: BTreeSet<String> = BTreeSet::new();
+    let mut edges: Vec<(String, Vec<String>, Option<String>)> = Vec::new();
+    let mut node_defaults: BTreeMap<String, String> = BTreeMap::new();
+    for statement in statements(body) {
+        let (target, attrs) = attributes(&statement);
+        if target == "node" {
+            node_defaults.extend(attrs);
+            continue;
+        }
+        if matches!(target, "graph" | "edge") || (target.contains('=') && attrs.is_empty()) {
+            continue;
+        }
+
+        let mut node = |name: &String, attrs: &BTreeMap<String, String>| {
+            if names.contains(name) && attrs.is_empty() {
+                return;
+            }
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+            let attr = |key: &str| {
+                attrs
+                    .get(key)
+                    .or(node_defaults.get(key))
+                    .map(String::as_str)
+            };
+            if attr("shape") == Some("doublecircle") {
+                accepting.insert(name.clone());
+            }
+            if matches!(attr("shape"), Some("point" | "none" | "plaintext"))
+                || attr("style").is_some_and(|s| s.contains("invis"))
+                || attr("label").is_some_and(str::is_empty)
+            {
+                markers.insert(name.clone());
+            }
+        };
+        match target.split_once("->") {
+            Some((from, to)) => {
+                let (from, to) = (unquote(from), node_ids(to));
+                for name in std::iter::once(&from).chain(&to) {
+                    node(name, &BTreeMap::new());
+                }
+                edges.push((from, to, attrs.get("label").cloned()));
+            }
+            None => node(&unquote(target), &attrs),
+        }
+    }
+
+    let targets: BTreeSet<&String> = edges.iter().flat_map(|(_, to, _)| to).collect();
+    let is_marker = |n: &String| (markers.contains(n) || n.is_empty()) && !targets.contains(n);
+    let start = edges
+        .iter()
+        .find(|(from, _, _)| is_marker(from))
+        .and_then(|(_, to, _)| to.first())
+        .or_else(|| edges.first().map(|(from, _, _)| from))
+        .or_else(|| names.iter().find(|n| !is_marker(n)))
+        .cloned();
+    let names: Vec<String> = names.into_iter().filter(|n| !is_marker(n)).collect();
+    if names.is_empty() {
+        return Err(DotError::NoStates);
+    }
+    let ids = assign_ids(&names, start.as_ref());
+
+    let mut alphabet: Vec<char> = Vec::new();
+    let mut states: BTreeMap<State, (bool, Transitions)> = ids
+        .iter()
+        .map(|(name, id)| (*id, (accepting.contains(*name), Transitions::new())))
+        .collect();
+    for (from, to, label) in edges.iter().filter(|(from, _, _)| !is_marker(from)) {
+        let label = label
+            .as_ref()
+            .ok_or_else(|| DotError::MissingLabel(from.clone(), to.join(",")))?;
+        let transitions = parse_label(label).map_err(DotError::InvalidLabel)?;
+        for t in &transitions {
+            if let Transition::Char(c) = t
+                && !alphabet.contains(c)
+            {
+                alphabet.push(*c);
+            }
+        }
+        let entry = &mut states.get_mut(&ids[from]).expect("state was interned").1;
+        for t in transitions {
+            entry
+                .entry(t)
+                .or_default()
+                .extend(to.iter().map(|n| ids[n]));
+        }
+    }
+
+    Ok(NFA::from_parts(states, alphabet).with_labels(labels(&ids)))
+}
@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::{
+    BuildError, LimitExceeded, ParseError, TableParseError, bytes::BytesError,
+    codegen::CodegenError, dot::DotError, grammar::GrammarError, jflap::JflapError,
+    mermaid::MermaidError, pda::PdaError, tm::TmError, validate::InvariantViolation,
+};
+
+/// Any error the library returns, for callers that handle them all the same way
+#[derive(Debug)]
+pub enum FaVizError {
+    Parse(ParseError),
+    TableParse(TableParseError),
+    Build(BuildError),
+    Dot(DotError),
+    Mermaid(MermaidError),
+    Jflap(JflapError),
+    Codegen(CodegenError),
+    Grammar(GrammarError),
+    Pda(PdaError),
+    Tm(TmError),
+    Bytes(BytesError),
+    Limit(LimitExceeded),
+    Invariant(InvariantViolation),
+}
+
+impl FaVizError {
+    fn inner(&self) -> &(dyn Error + 'static) {
+        match self {
+            FaVizError::Parse(e) => e,
+            FaVizError::TableParse(e) => e,
+            FaVizError::Build(e) => e,
+            FaVizError::Dot(e) => e,
+            FaVizError::Mermaid(e) => e,
+            FaVizError::Jflap(e) => e,
+            FaVizError::Codegen(e) => e,
+            FaVizError::Grammar(e) => e,
+            FaVizError::Pda(e) => e,
+            FaVizError::Tm(e) => e,
+            FaVizError::Bytes(e) => e,
+            FaVizError::Limit(e) => e,
+            FaVizError::Invariant(e) => e,
+        }
+    }
+}
+
+impl Error for FaVizError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.inner())
+    }
+}
+
+impl Display for FaVizError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl From<ParseError> for FaVizError {
+    fn from(value: ParseError) -> Self {
+        FaVizError::Parse(value)
+    }
+}
+
+impl From<TableParseError> for FaVizError {
+    fn from(value: TableParseError) -> Self {
+        FaVizError::TableParse(value)
+    }
+}
+
+impl From<BuildError> for FaVizError {
+    fn from(value: BuildError) -> Self {
+        FaVizError::Build(value)
+    }
+}
+
+impl From<DotError> for FaVizError {
+    fn from(value: DotError) -> Self {
+        FaVizError::Dot(value)
+    }
+}
+
+impl From<MermaidError> for FaVizError {
+    fn from(value: MermaidError) -> Self {
+        FaVizError::Mermaid(value)
+    }
+}
+
+impl From<JflapError> for FaVizError {
+    fn from(value: JflapError) -> Self {
+        FaVizError::Jflap(value)
+    }
+}
+
+impl From<CodegenError> for FaVizError {
+    fn from(value: CodegenError) -> Self {
+        FaVizError::Codegen(value)
+    }
+}
+
+impl From<GrammarError> for FaVizError {
+    fn from(value: GrammarError) -> Self {
+        FaVizError::Grammar(value)
+    }
+}
+
+impl From<PdaError> for FaVizError {
+    fn from(value: PdaError) -> Self {
+        FaVizError::Pda(value)
+    }
+}
+
+impl From<TmError> for FaVizError {
+    fn from(value: TmError) -> Self {
+        FaVizError::Tm(value)
+    }
+}
+
+impl From<BytesError> for FaVizError {
+    fn from(value: BytesError) -> Self {
+        FaVizError::Bytes(value)
+    }
+}
+
+impl From<LimitExceeded> for FaVizError {
+    fn from(value: LimitExceeded) -> Self {
+        FaVizError::Limit(value)
+    }
+}
+
+impl From<InvariantViolation> for FaVizError {
+    fn from(value: InvariantViolation) -> Self {
+        FaVizError::Invariant(value)
+    }
+}
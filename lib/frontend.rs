@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use crate::{
+    Automaton, FaVizError, LabeledTable, NFA, ParseOptions, dot, jflap, mermaid, nfa::directive,
+};
+
+/// A parser for one on-disk automaton format.
+pub trait InputFrontend {
+    fn name(&self) -> &'static str;
+    /// Cheap check of whether the input looks like this format
+    fn detect(&self, s: &str) -> bool;
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError>;
+}
+
+/// Native NFA definition format, first line is `<state count> <lambda> <alphabet...>` or
+/// `key: value` directives
+pub struct NfaFrontend;
+
+impl InputFrontend for NfaFrontend {
+    fn name(&self) -> &'static str {
+        "nfa"
+    }
+
+    fn detect(&self, s: &str) -> bool {
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .peekable();
+        let mut directives = false;
+        while lines.next_if(|l| directive(l).is_some()).is_some() {
+            directives = true;
+        }
+        let positional = lines
+            .next_if(|l| {
+                l.split_whitespace()
+                    .next()
+                    .is_some_and(|n| n.parse::<usize>().is_ok())
+            })
+            .is_some();
+        // Binary marked tables also start with a number, NFA rows are always `+` or `-`
+        (directives || positional)
+            && lines
+                .next()
+                .is_none_or(|l| l.starts_with('+') || l.starts_with('-'))
+    }
+
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError> {
+        Ok(Automaton::Nfa(NFA::from_str(s)?))
+    }
+}
+
+/// Transition table format, every row is `<+|-|1|0> <id> <transitions...>` with an optional
+/// alphabet line first, see [`LabeledTable::parse`]
+pub struct TableFrontend;
+
+impl InputFrontend for TableFrontend {
+    fn name(&self) -> &'static str {
+        "table"
+    }
+
+    fn detect(&self, s: &str) -> bool {
+        let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+        let is_row = |l: &str| matches!(l.split_whitespace().next(), Some("+" | "-" | "1" | "0"));
+        match (lines.next(), lines.next()) {
+            (Some(first), _) if first.starts_with('+') || first.starts_with('-') => true,
+            (Some(_), Some(second)) => is_row(second),
+            _ => false,
+        }
+    }
+
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError> {
+        Ok(Automaton::Table(LabeledTable::parse(
+            s,
+            &ParseOptions::default(),
+        )?))
+    }
+}
+
+/// Mermaid `stateDiagram-v2` text
+pub struct MermaidFrontend;
+
+impl InputFrontend for MermaidFrontend {
+    fn name(&self) -> &'static str {
+        "mermaid"
+    }
+
+    fn detect(&self, s: &str) -> bool {
+        mermaid::is_mermaid(s)
+    }
+
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError> {
+        Ok(Automaton::Nfa(mermaid::parse(s)?))
+    }
+}
+
+/// JFLAP `.jff` XML, finite automata only
+pub struct JflapFrontend;
+
+impl InputFrontend for JflapFrontend {
+    fn name(&self) -> &'static str {
+        "jff"
+    }
+
+    fn detect(&self, s: &str) -> bool {
+        jflap::is_jflap(s)
+    }
+
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError> {
+        Ok(Automaton::Nfa(jflap::parse(s)?))
+    }
+}
+
+/// Graphviz DOT digraph with labeled edges
+pub struct DotFrontend;
+
+impl InputFrontend for DotFrontend {
+    fn name(&self) -> &'static str {
+        "dot"
+    }
+
+    fn detect(&self, s: &str) -> bool {
+        dot::is_dot(s)
+    }
+
+    fn parse(&self, s: &str) -> Result<Automaton, FaVizError> {
+        Ok(Automaton::Nfa(dot::parse(s)?))
+    }
+}
+
+/// All known input formats, in detection order
+pub fn frontends() -> Vec<Box<dyn InputFrontend>> {
+    vec![
+        Box::new(NfaFrontend),
+        Box::new(DotFrontend),
+        Box::new(TableFrontend),
+        Box::new(MermaidFrontend),
+        Box::new(JflapFrontend),
+    ]
+}
+
+pub fn frontend_by_name(name: &str) -> Option<Box<dyn InputFrontend>> {
+    frontends().into_iter().find(|f| f.name() == name)
+}
+
+pub fn detect(s: &str) -> Option<Box<dyn InputFrontend>> {
+    frontends().into_iter().find(|f| f.detect(s))
+}
@@ -0,0 +1,317 @@
+//! Right-linear grammars and their conversion to and from NFAs
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{
+    NFA,
+    import::{assign_ids, labels},
+    nfa::{State, Transition, Transitions},
+};
+
+#[derive(Debug)]
+pub enum GrammarError {
+    /// Line number of a rule without `->`
+    MissingArrow(usize),
+    InvalidNonterminal(String),
+    /// Line number of a production with symbols after its nonterminal
+    NotRightLinear(usize),
+    /// Line number of a `<name>` or `\` escape that is cut off
+    Unterminated(usize),
+    NoRules,
+}
+impl Error for GrammarError {}
+impl Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarError::MissingArrow(line) => {
+                write!(f, "MissingArrow: Line {} has no ->!", line)
+            }
+            GrammarError::InvalidNonterminal(s) => {
+                write!(f, "InvalidNonterminal: {:?} is not a nonterminal!", s)
+            }
+            GrammarError::NotRightLinear(line) => write!(
+                f,
+                "NotRightLinear: A production on line {} continues after its nonterminal!",
+                line
+            ),
+            GrammarError::Unterminated(line) => {
+                write!(f, "Unterminated: Line {} ends inside a symbol!", line)
+            }
+            GrammarError::NoRules => write!(f, "NoRules: Grammar has no rules!"),
+        }
+    }
+}
+
+/// A string of terminals optionally followed by a nonterminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Production {
+    pub terminals: Vec<char>,
+    pub next: Option<String>,
+}
+
+/// A right-linear grammar, the nonterminal of the first rule being the start symbol
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Grammar {
+    pub rules: Vec<(String, Vec<Production>)>,
+}
+
+impl Grammar {
+    pub fn start(&self) -> Option<&str> {
+        self.rules.first().map(|(name, _)| name.as_str())
+    }
+
+    fn rule(&mut self, name: &str) -> &mut Vec<Production> {
+        let i = match self.rules.iter().position(|(n, _)| n == name) {
+            Some(i) => i,
+            None => {
+                self.rules.push((name.to_owned(), Vec::new()));
+                self.rules.len() - 1
+            }
+        };
+        &mut self.rules[i].1
+    }
+}
+
+fn nonterminal(name: &str) -> String {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => name.to_owned(),
+        _ => format!("<{}>", name),
+    }
+}
+
+fn terminal(c: char) -> String {
+    match c.is_ascii_uppercase() || c.is_whitespace() || "<>|\\&ελ".contains(c) {
+        true => format!("\\{}", c),
+        false => c.to_string(),
+    }
+}
+
+impl Display for Production {
+    /// Terminals that could be read as something else are escaped with `\`, the empty string is
+    /// written `ε`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.terminals.is_empty() && self.next.is_none() {
+            return write!(f, "ε");
+        }
+        for c in &self.terminals {
+            write!(f, "{}", terminal(*c))?;
+        }
+        match &self.next {
+            Some(next) => write!(f, "{}", nonterminal(next)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Grammar {
+    /// One `A -> aB | b` line per nonterminal with productions
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, productions) in self.rules.iter().filter(|(_, p)| !p.is_empty()) {
+            let alternatives: Vec<String> = productions.iter().map(|p| p.to_string()).collect();
+            writeln!(f, "{} -> {}", nonterminal(name), alternatives.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the right side of a rule into productions
+fn productions(s: &str, line: usize) -> Result<Vec<Production>, GrammarError> {
+    let mut productions = vec![Production {
+        terminals: Vec::new(),
+        next: None,
+    }];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        let current = productions.last_mut().expect("starts with one production");
+        let symbol = match c {
+            '|' => {
+                productions.push(Production {
+                    terminals: Vec::new(),
+                    next: None,
+                });
+                continue;
+            }
+            '&' | 'ε' | 'λ' => continue,
+            c if c.is_whitespace() => continue,
+            '\\' => Err(chars.next().ok_or(GrammarError::Unterminated(line))?),
+            '<' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('>') => break Ok(name),
+                        Some(c) => name.push(c),
+                        None => return Err(GrammarError::Unterminated(line)),
+                    }
+                }
+            }
+            c if c.is_ascii_uppercase() => Ok(c.to_string()),
+            c => Err(c),
+        };
+        if current.next.is_some() {
+            return Err(GrammarError::NotRightLinear(line));
+        }
+        match symbol {
+            Ok(name) => current.next = Some(name),
+            Err(c) => current.terminals.push(c),
+        }
+    }
+    Ok(productions)
+}
+
+impl FromStr for Grammar {
+    type Err = GrammarError;
+
+    /// Rules are `A -> aB | b | ε`, nonterminals being uppercase letters or `<names>` and every
+    /// other character a terminal. `ε`, `λ` or `&` (or nothing) stand for the empty string and
+    /// `\` makes the next character a terminal. Several rules for one nonterminal are merged.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grammar = Grammar::default();
+        for (number, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (lhs, rhs) = line
+                .split_once("->")
+                .or_else(|| line.split_once('→'))
+                .ok_or(GrammarError::MissingArrow(number + 1))?;
+            let lhs = lhs.trim();
+            let name = match productions(lhs, number + 1)?.as_slice() {
+                [
+                    Production {
+                        terminals,
+                        next: Some(name),
+                    },
+                ] if terminals.is_empty() => name.clone(),
+                _ => return Err(GrammarError::InvalidNonterminal(lhs.to_owned())),
+            };
+            let productions = productions(rhs, number + 1)?;
+            grammar.rule(&name).extend(productions);
+        }
+        match grammar.rules.is_empty() {
+            true => Err(GrammarError::NoRules),
+            false => Ok(grammar),
+        }
+    }
+}
+
+/// One state per nonterminal, named after it, and one per terminal beyond the first in a
+/// production. Productions ending in a terminal lead to a shared accepting state, `A -> ε` makes
+/// `A` accepting and `A -> B` becomes a lambda transition.
+impl From<&Grammar> for NFA {
+    fn from(value: &Grammar) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        for (name, productions) in &value.rules {
+            for name in std::iter::once(name).chain(productions.iter().flat_map(|p| &p.next)) {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        let ids = assign_ids(&names, names.first());
+        let mut states: BTreeMap<State, (bool, Transitions)> = ids
+            .values()
+            .map(|id| (*id, (false, Transitions::new())))
+            .collect();
+        let mut alphabet: Vec<char> = Vec::new();
+        let mut fresh = ids.values().max().map_or(0, |id| id + 1);
+        let mut accepting: Option<State> = None;
+
+        for (name, productions) in &value.rules {
+            for production in productions {
+                let mut from = ids[name];
+                let target = production.next.as_ref().map(|next| ids[next]);
+                let Some((last, initial)) = production.terminals.split_last() else {
+                    match target {
+                        Some(to) => {
+                            let transitions = &mut states.get_mut(&from).expect("interned").1;
+                            transitions
+                                .entry(Transition::Lambda)
+                                .or_default()
+                                .insert(to);
+                        }
+                        None => states.get_mut(&from).expect("interned").0 = true,
+                    }
+                    continue;
+                };
+                let mut edge = |from: State, c: char, to: State| {
+                    if !alphabet.contains(&c) {
+                        alphabet.push(c);
+                    }
+                    let transitions = &mut states.entry(from).or_default().1;
+                    transitions
+                        .entry(Transition::Char(c))
+                        .or_default()
+                        .insert(to);
+                };
+                for c in initial {
+                    edge(from, *c, fresh);
+                    from = fresh;
+                    fresh += 1;
+                }
+                let to = target.unwrap_or_else(|| {
+                    *accepting.get_or_insert_with(|| {
+                        fresh += 1;
+                        fresh - 1
+                    })
+                });
+                edge(from, *last, to);
+            }
+        }
+        if let Some(state) = accepting {
+            states.entry(state).or_default().0 = true;
+        }
+
+        NFA::from_parts(states, alphabet).with_labels(labels(&ids))
+    }
+}
+
+/// One nonterminal per state: its label if the NFA has any, a letter from `A` in state order if
+/// there are few enough states, or its number
+impl From<&NFA> for Grammar {
+    fn from(value: &NFA) -> Self {
+        let states = value.states();
+        let letters = value.labels().is_empty() && states.len() <= 26;
+        let names: BTreeMap<State, String> = states
+            .keys()
+            .enumerate()
+            .map(|(i, id)| {
+                let name = match value.labels().get(id) {
+                    Some(label) => label.clone(),
+                    None if letters => char::from(b'A' + i as u8).to_string(),
+                    None => id.to_string(),
+                };
+                (*id, name)
+            })
+            .collect();
+
+        let rules = states
+            .iter()
+            .map(|(id, (accepting, transitions))| {
+                let mut productions: Vec<Production> = transitions
+                    .iter()
+                    .flat_map(|(t, targets)| {
+                        targets.iter().map(|to| Production {
+                            terminals: match t {
+                                Transition::Char(c) => vec![*c],
+                                Transition::Lambda => Vec::new(),
+                            },
+                            next: Some(names[to].clone()),
+                        })
+                    })
+                    .collect();
+                if *accepting {
+                    productions.push(Production {
+                        terminals: Vec::new(),
+                        next: None,
+                    });
+                }
+                (names[id].clone(), productions)
+            })
+            .collect();
+        Grammar { rules }
+    }
+}
@@ -0,0 +1,168 @@
+//! Helpers shared by the importers of graph-shaped formats
+
+use alphabet_encoding::decode;
+use std::collections::BTreeMap;
+
+use crate::nfa::{State, Transition, named_symbol};
+
+/// The character `&#N;`, or Mermaid's `#N;`, refers to
+fn character_reference(s: &str) -> Option<char> {
+    let number = s.strip_prefix('&').unwrap_or(s).strip_prefix('#')?;
+    char::from_u32(number.strip_suffix(';')?.parse().ok()?)
+}
+
+/// Splits an edge label like `a,b|&lambda;` into transitions, `Err` holds the piece that is
+/// neither a single character, an escaped or encoded one nor a lambda spelling. A `|` or `,`
+/// symbol has to be written as a character reference like `&#124;`.
+pub(crate) fn parse_label(label: &str) -> Result<Vec<Transition>, String> {
+    label
+        .split([',', '|'])
+        .map(str::trim)
+        .map(|l| {
+            l.strip_prefix("<i>")
+                .and_then(|l| l.strip_suffix("</i>"))
+                .unwrap_or(l)
+        })
+        .map(|l| match l {
+            "" | "ε" | "λ" | "&lambda;" | "&epsilon;" | "epsilon" | "lambda" => {
+                Ok(Transition::Lambda)
+            }
+            l if let Some(c) = named_symbol(l) => Ok(Transition::Char(c)),
+            "&lt;" => Ok(Transition::Char('<')),
+            "&gt;" => Ok(Transition::Char('>')),
+            "&amp;" => Ok(Transition::Char('&')),
+            "&quot;" => Ok(Transition::Char('"')),
+            l if let Some(c) = character_reference(l) => Ok(Transition::Char(c)),
+            l => {
+                let decoded = decode(l.to_owned()).unwrap_or_default();
+                let mut chars = decoded.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Transition::Char(c)),
+                    _ => Err(l.to_owned()),
+                }
+            }
+        })
+        .collect()
+}
+
+/// The id a state name spells, if it is written exactly as that id is, so `01` and `+1` are names
+fn numeric(name: &str) -> Option<State> {
+    name.parse::<State>()
+        .ok()
+        .filter(|id| id.to_string() == name)
+}
+
+/// Numeric state names are kept if the start state is already the lowest, otherwise states are
+/// numbered in order of appearance with the start state first.
+pub(crate) fn assign_ids<'a>(
+    names: &'a [String],
+    start: Option<&'a String>,
+) -> BTreeMap<&'a String, State> {
+    let numeric: Option<BTreeMap<&String, State>> =
+        names.iter().map(|n| numeric(n).map(|id| (n, id))).collect();
+    match numeric {
+        Some(ids) if start.is_none_or(|s| ids.values().min() == ids.get(s)) => ids,
+        _ => start
+            .into_iter()
+            .chain(names.iter().filter(|n| Some(*n) != start))
+            .enumerate()
+            .map(|(id, n)| (n, id))
+            .collect(),
+    }
+}
+
+/// Original names of the states [`assign_ids`] did not number after themselves
+pub(crate) fn labels(ids: &BTreeMap<&String, State>) -> BTreeMap<State, String> {
+    ids.iter()
+        .filter(|(name, id)| numeric(name) != Some(**id))
+        .map(|(name, id)| (*id, (*name).clone()))
+        .collect()
+}
+
+/// `key: value` with one of `keys` as the key, for the directives at the top of definition files
+pub(crate) fn directive<'a>(line: &'a str, keys: &[&str]) -> Option<(&'a str, &'a str)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    keys.contains(&key).then(|| (key, value.trim()))
+}
+
+/// What can be wrong with the shape of a [`MachineDefinition`], before any field is read
+#[derive(Debug)]
+pub(crate) enum DefinitionError {
+    /// Line number of a row without exactly six fields
+    ColumnMismatch(usize),
+    NoTransitions,
+    UnknownStart(String),
+}
+
+/// A row `<+|-> <from> <to>` followed by three fields that depend on the machine
+pub(crate) struct MachineRow<'a> {
+    pub(crate) accepting: bool,
+    pub(crate) from: &'a str,
+    pub(crate) to: &'a str,
+    pub(crate) fields: [&'a str; 3],
+}
+
+/// The layout shared by the Turing machine and pushdown automaton formats: leading directives,
+/// then six-field rows. The start state is the `start:` directive's or the first row's `from`.
+pub(crate) struct MachineDefinition<'a> {
+    pub(crate) directives: BTreeMap<&'a str, &'a str>,
+    pub(crate) rows: Vec<MachineRow<'a>>,
+    /// Every state name in order of appearance
+    names: Vec<String>,
+    start: String,
+}
+
+impl<'a> MachineDefinition<'a> {
+    /// Lines before the first row whose key is one of `keys` are directives
+    pub(crate) fn parse(s: &'a str, keys: &[&str]) -> Result<Self, DefinitionError> {
+        let mut directives = BTreeMap::new();
+        let mut rows = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            if let Some((key, value)) = directive(line, keys).filter(|_| rows.is_empty()) {
+                directives.insert(key, value);
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [] => {}
+                [marker, from, to, a, b, c] => rows.push(MachineRow {
+                    accepting: *marker == "+",
+                    from,
+                    to,
+                    fields: [a, b, c],
+                }),
+                _ => return Err(DefinitionError::ColumnMismatch(number + 1)),
+            }
+        }
+        if rows.is_empty() {
+            return Err(DefinitionError::NoTransitions);
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        for row in &rows {
+            for name in [row.from, row.to] {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_owned());
+                }
+            }
+        }
+        let start = directives
+            .get("start")
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| names[0].clone());
+        if !names.contains(&start) {
+            return Err(DefinitionError::UnknownStart(start));
+        }
+        Ok(Self {
+            directives,
+            rows,
+            names,
+            start,
+        })
+    }
+
+    /// State ids by name from [`assign_ids`], the start state lowest
+    pub(crate) fn ids(&self) -> BTreeMap<&String, State> {
+        assign_ids(&self.names, Some(&self.start))
+    }
+}
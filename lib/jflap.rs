@@ -0,0 +1,253 @@
+//! Import and export of JFLAP `.jff` finite automaton files
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::{
+    NFA,
+    nfa::{State, Transition, Transitions},
+};
+
+#[derive(Debug)]
+pub enum JflapError {
+    NotFiniteAutomaton,
+    MissingElement(&'static str),
+    InvalidStateId(String),
+    UnknownState(String),
+    InvalidSymbol(String),
+    NoStates,
+}
+impl Error for JflapError {}
+impl Display for JflapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JflapError::NotFiniteAutomaton => {
+                write!(f, "NotFiniteAutomaton: Expected <type>fa</type>!")
+            }
+            JflapError::MissingElement(e) => {
+                write!(f, "MissingElement: Expected a <{}> element!", e)
+            }
+            JflapError::InvalidStateId(id) => {
+                write!(f, "InvalidStateId: Unable to parse state id {:?}!", id)
+            }
+            JflapError::UnknownState(id) => {
+                write!(
+                    f,
+                    "UnknownState: Transition uses undeclared state {:?}!",
+                    id
+                )
+            }
+            JflapError::InvalidSymbol(s) => {
+                write!(
+                    f,
+                    "InvalidSymbol: Transition reads {:?}, not a single symbol!",
+                    s
+                )
+            }
+            JflapError::NoStates => write!(f, "NoStates: Automaton has no states!"),
+        }
+    }
+}
+
+pub fn is_jflap(s: &str) -> bool {
+    s.contains("<structure") && s.contains("<type>")
+}
+
+/// Every `<tag ...>body</tag>` or `<tag .../>` in `s` as `(attributes, body)`, not nested
+fn elements<'a>(s: &'a str, tag: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(&open)? + open.len();
+            let after = &rest[start..];
+            // `<transition` must not match `<transitions`
+            if !after.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+                rest = after;
+                continue;
+            }
+            let end = after.find('>')?;
+            let attributes = &after[..end];
+            if let Some(attributes) = attributes.strip_suffix('/') {
+                rest = &after[end + 1..];
+                return Some((attributes, ""));
+            }
+            let body_end = after.find(&close)?;
+            let body = &after[end + 1..body_end];
+            rest = &after[body_end + close.len()..];
+            return Some((attributes, body));
+        }
+    })
+}
+
+fn element<'a>(s: &'a str, tag: &'a str) -> Option<&'a str> {
+    elements(s, tag).next().map(|(_, body)| body)
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    let start = attributes
+        .match_indices(&pattern)
+        .find(|(i, _)| *i == 0 || attributes[..*i].ends_with(char::is_whitespace))?
+        .0
+        + pattern.len();
+    let len = attributes[start..].find('"')?;
+    Some(&attributes[start..start + len])
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parses the `<automaton>` of a JFLAP file of type `fa`. Empty `<read/>` elements are lambda
+/// transitions, layout and labels are ignored.
+///
+/// JFLAP ids are kept if the initial state already has the lowest one, otherwise states are
+/// renumbered in document order with the initial state first. State names other than JFLAP's
+/// default `q<id>` are kept as labels.
+pub fn parse(s: &str) -> Result<NFA, JflapError> {
+    if element(s, "type").map(str::trim) != Some("fa") {
+        return Err(JflapError::NotFiniteAutomaton);
+    }
+    // Files from older JFLAP versions list states directly under <structure>
+    let automaton = element(s, "automaton").unwrap_or(s);
+
+    let mut declared: Vec<(State, bool, bool)> = Vec::new();
+    let mut names: BTreeMap<State, String> = BTreeMap::new();
+    for (attributes, body) in elements(automaton, "state") {
+        let id = attribute(attributes, "id").ok_or(JflapError::MissingElement("state id"))?;
+        let id = id
+            .parse::<State>()
+            .map_err(|_| JflapError::InvalidStateId(id.to_owned()))?;
+        let initial = elements(body, "initial").next().is_some();
+        let accepting = elements(body, "final").next().is_some();
+        declared.push((id, initial, accepting));
+        // JFLAP names new states `q<id>`, only names someone chose are worth keeping
+        if let Some(name) = attribute(attributes, "name").map(unescape)
+            && name != format!("q{}", id)
+        {
+            names.insert(id, name);
+        }
+    }
+    if declared.is_empty() {
+        return Err(JflapError::NoStates);
+    }
+
+    let start = declared
+        .iter()
+        .find(|(_, initial, _)| *initial)
+        .map(|s| s.0);
+    let lowest = declared.iter().map(|s| s.0).min();
+    let ids: BTreeMap<State, State> = match start {
+        Some(start) if Some(start) != lowest => std::iter::once(start)
+            .chain(declared.iter().map(|s| s.0).filter(|id| *id != start))
+            .enumerate()
+            .map(|(new, old)| (old, new))
+            .collect(),
+        _ => declared.iter().map(|s| (s.0, s.0)).collect(),
+    };
+
+    let mut states: BTreeMap<State, (bool, Transitions)> = declared
+        .iter()
+        .map(|(id, _, accepting)| (ids[id], (*accepting, Transitions::new())))
+        .collect();
+    let mut alphabet: Vec<char> = Vec::new();
+    for (_, body) in elements(automaton, "transition") {
+        let endpoint = |tag: &'static str| -> Result<State, JflapError> {
+            let id = element(body, tag)
+                .ok_or(JflapError::MissingElement(tag))?
+                .trim();
+            id.parse::<State>()
+                .ok()
+                .and_then(|id| ids.get(&id).copied())
+                .ok_or_else(|| JflapError::UnknownState(id.to_owned()))
+        };
+        let (from, to) = (endpoint("from")?, endpoint("to")?);
+        let read = unescape(element(body, "read").unwrap_or_default());
+        let mut chars = read.chars();
+        let transition = match (chars.next(), chars.next()) {
+            (None, _) => Transition::Lambda,
+            (Some(c), None) => Transition::Char(c),
+            _ => return Err(JflapError::InvalidSymbol(read)),
+        };
+        if let Transition::Char(c) = transition
+            && !alphabet.contains(&c)
+        {
+            alphabet.push(c);
+        }
+        states
+            .get_mut(&from)
+            .expect("state was declared")
+            .1
+            .entry(transition)
+            .or_default()
+            .insert(to);
+    }
+
+    let labels = names
+        .into_iter()
+        .map(|(id, name)| (ids[&id], name))
+        .collect();
+    Ok(NFA::from_parts(states, alphabet).with_labels(labels))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Writes `nfa` as a JFLAP file that [`parse`] reads back. States are laid out on a circle so
+/// the file opens with something readable, JFLAP will not lay them out itself.
+pub fn serialize(nfa: &NFA) -> String {
+    const RADIUS: f64 = 200.0;
+
+    let states = nfa.states();
+    let start = nfa.start();
+    let count = states.len().max(1) as f64;
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n<structure>\n\t<type>fa</type>\n\t<automaton>\n",
+    );
+    for (i, (id, (accepting, _))) in states.iter().enumerate() {
+        let angle = std::f64::consts::TAU * i as f64 / count;
+        let name = nfa
+            .labels()
+            .get(id)
+            .map_or_else(|| format!("q{}", id), |l| escape(l));
+        out += &format!("\t\t<state id=\"{}\" name=\"{}\">\n", id, name);
+        out += &format!("\t\t\t<x>{:.1}</x>\n", RADIUS * (1.0 + angle.cos()) + 50.0);
+        out += &format!("\t\t\t<y>{:.1}</y>\n", RADIUS * (1.0 + angle.sin()) + 50.0);
+        if Some(*id) == start {
+            out += "\t\t\t<initial/>\n";
+        }
+        if *accepting {
+            out += "\t\t\t<final/>\n";
+        }
+        out += "\t\t</state>\n";
+    }
+    for (from, (_, transitions)) in &states {
+        for (transition, targets) in transitions {
+            let read = match transition {
+                Transition::Lambda => "\t\t\t<read/>\n".to_owned(),
+                Transition::Char(c) => format!("\t\t\t<read>{}</read>\n", escape(&c.to_string())),
+            };
+            for to in targets {
+                out += "\t\t<transition>\n";
+                out += &format!("\t\t\t<from>{}</from>\n", from);
+                out += &format!("\t\t\t<to>{}</to>\n", to);
+                out += &read;
+                out += "\t\t</transition>\n";
+            }
+        }
+    }
+    out += "\t</automaton>\n</structure>\n";
+    out
+}
@@ -1,5 +1,60 @@
+//! Finite automata parsing, conversion and visualization.
+//!
+//! Automata are read as an [`NFA`] from the native definition format or as a [`LabeledTable`]
+//! (a DFA as a transition table), or through [`frontend`] from any supported format including
+//! [`dot`], [`mermaid`] and [`jflap`]. [`Automaton`] holds either one, and [`FiniteAutomaton`]
+//! is the view of states and edges all of them share. From there they can be
+//! determinized, minimized, compared with [`diff`], split into [`nerode`] classes, rendered as a
+//! [`Digraph`], converted to and from right-linear [`grammar`]s, or turned into code with
+//! [`codegen`] and HTML with [`report`].
+//! Pushdown automata live in [`pda`] and Turing machines in [`tm`], DFAs over raw bytes in
+//! [`bytes`].
+//!
+//! Every error type converts into [`FaVizError`]. The command line tool's dependencies sit
+//! behind the default `cli` feature, `default-features = false` leaves only the library.
+//! Counting accepted strings exactly needs the `bigint` feature, sampling them the `rand` one.
+
+mod automaton;
+pub mod bytes;
+pub mod codegen;
+pub mod diff;
 mod digraph;
+mod distance;
+pub mod dot;
+mod error;
+pub mod frontend;
+pub mod grammar;
+mod import;
+pub mod jflap;
+pub mod mermaid;
+pub mod nerode;
 mod nfa;
+pub mod pda;
+pub mod report;
+mod run;
+#[cfg(feature = "bigint")]
+mod sample;
+mod stats;
+mod table;
+pub mod tm;
+mod tokens;
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use automaton::{Automaton, AutomatonKind, FiniteAutomaton};
 pub use digraph::Digraph;
-pub use nfa::NFA;
+pub use error::FaVizError;
+pub use frontend::InputFrontend;
+#[cfg(feature = "test-util")]
+pub use nfa::testing;
+pub use nfa::{
+    LabelStyle, LazyDfa, LimitExceeded, Limits, Minimizer, NFA, ParseError, ParseErrorKind,
+    Progress, Stage, Transition, Worksheet, WorksheetRow,
+};
+pub use run::DfaRun;
+pub use stats::Stats;
+pub use table::{
+    AcceptingMarkers, BuildError, LabeledTable, ParseOptions, Reachability, SerializeOptions,
+    TableBuilder, TableFormat, TableParseError, TransitionTableExt,
+};
@@ -0,0 +1,110 @@
+//! Import of Mermaid `stateDiagram-v2` text
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::{
+    NFA,
+    import::{assign_ids, labels, parse_label},
+    nfa::{State, Transition, Transitions},
+};
+
+#[derive(Debug)]
+pub enum MermaidError {
+    MissingHeader,
+    InvalidLabel(String),
+    NoStates,
+}
+impl Error for MermaidError {}
+impl Display for MermaidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MermaidError::MissingHeader => {
+                write!(
+                    f,
+                    "MissingHeader: Expected stateDiagram or stateDiagram-v2!"
+                )
+            }
+            MermaidError::InvalidLabel(l) => {
+                write!(f, "InvalidLabel: Unable to parse transition label {:?}!", l)
+            }
+            MermaidError::NoStates => write!(f, "NoStates: Diagram has no transitions!"),
+        }
+    }
+}
+
+const START_END: &str = "[*]";
+
+pub fn is_mermaid(s: &str) -> bool {
+    s.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("%%"))
+        .is_some_and(|l| l.starts_with("stateDiagram"))
+}
+
+/// Parses `A --> B : label` edges, `[*] --> A` for the start and `A --> [*]` for accepting states.
+/// Other statements (notes, state declarations, styling) are ignored. State ids are assigned by
+/// [`assign_ids`], other names are kept as labels.
+pub fn parse(s: &str) -> Result<NFA, MermaidError> {
+    if !is_mermaid(s) {
+        return Err(MermaidError::MissingHeader);
+    }
+
+    let mut start: Option<String> = None;
+    let mut accepting: BTreeSet<String> = BTreeSet::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut edges: Vec<(String, String, Vec<Transition>)> = Vec::new();
+    let mut alphabet: Vec<char> = Vec::new();
+
+    for line in s.lines().map(str::trim) {
+        let Some((from, rest)) = line.split_once("-->") else {
+            continue;
+        };
+        let (to, label) = rest.split_once(':').unwrap_or((rest, ""));
+        let (from, to) = (from.trim().to_owned(), to.trim().to_owned());
+
+        for name in [&from, &to] {
+            if name != START_END && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        match (from.as_str(), to.as_str()) {
+            (START_END, START_END) => {}
+            (START_END, _) => start = Some(to),
+            (_, START_END) => {
+                accepting.insert(from);
+            }
+            _ => {
+                let transitions = parse_label(label).map_err(MermaidError::InvalidLabel)?;
+                for t in &transitions {
+                    if let Transition::Char(c) = t
+                        && !alphabet.contains(c)
+                    {
+                        alphabet.push(*c);
+                    }
+                }
+                edges.push((from, to, transitions));
+            }
+        }
+    }
+
+    if names.is_empty() {
+        return Err(MermaidError::NoStates);
+    }
+
+    let ids = assign_ids(&names, start.as_ref());
+
+    let mut states: BTreeMap<State, (bool, Transitions)> = ids
+        .iter()
+        .map(|(name, id)| (*id, (accepting.contains(*name), Transitions::new())))
+        .collect();
+    for (from, to, transitions) in &edges {
+        let entry = &mut states.get_mut(&ids[from]).expect("state was interned").1;
+        for t in transitions {
+            entry.entry(*t).or_default().insert(ids[to]);
+        }
+    }
+
+    Ok(NFA::from_parts(states, alphabet).with_labels(labels(&ids)))
+}
@@ -0,0 +1,378 @@
+//! Myhill–Nerode equivalence classes of the states of a DFA
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque, btree_map::Entry};
+use std::fmt::Display;
+
+use transition_tables::{TransitionTable, TransitionTableRow};
+
+use crate::{Digraph, LabeledTable, nfa::Transition};
+
+type State = usize;
+type Pair = (usize, usize);
+/// A block and the column whose transitions split it
+type Split = (usize, usize);
+
+/// States no string tells apart, which a minimal DFA merges into one
+pub struct Class {
+    /// In the order of the table's rows
+    pub states: Vec<State>,
+    pub accepting: bool,
+    /// Shortest string leading into the class, `None` if no string does
+    pub access: Option<String>,
+}
+
+pub struct Classes {
+    /// Ordered by their first state in the table, the start state's class first
+    pub classes: Vec<Class>,
+    /// Shortest suffix accepted after exactly one of two classes' access strings, keyed by the
+    /// indices of the classes in ascending order
+    pub distinguishing: BTreeMap<(usize, usize), String>,
+    names: BTreeMap<State, String>,
+}
+
+/// The table with missing transitions going to an extra dead state numbered after the rows
+struct Complete {
+    ids: Vec<State>,
+    accepting: Vec<bool>,
+    moves: Vec<Vec<usize>>,
+}
+
+impl Complete {
+    fn new(table: &LabeledTable) -> Self {
+        let rows = &table.table().rows;
+        let ids: Vec<State> = rows.iter().map(|r| r.id).collect();
+        let index: BTreeMap<State, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let dead = rows.len();
+        let mut moves: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|r| {
+                (0..table.alphabet().len())
+                    .map(|column| {
+                        r.transitions
+                            .get(column)
+                            .copied()
+                            .flatten()
+                            .and_then(|t| index.get(&t).copied())
+                            .unwrap_or(dead)
+                    })
+                    .collect()
+            })
+            .collect();
+        moves.push(vec![dead; table.alphabet().len()]);
+        let accepting = rows.iter().map(|r| r.accepting).chain([false]).collect();
+        Self {
+            ids,
+            accepting,
+            moves,
+        }
+    }
+
+    /// Splits blocks one at a time, first by acceptance and then repeatedly the first block
+    /// whose states lead into different blocks on some symbol, by the block they lead into. Every
+    /// step has the block of every state, blocks numbered in the order of their first state, and
+    /// the block and column that were split.
+    fn refine(&self) -> Vec<(Vec<usize>, Option<Split>)> {
+        let columns = self.moves.first().map_or(0, Vec::len);
+        let initial: Vec<usize> = self.accepting.iter().map(|a| usize::from(*a)).collect();
+        let mut steps = vec![(canonical(&initial), None)];
+        loop {
+            let block = &steps.last().expect("starts with the initial split").0;
+            let count = block.iter().max().map_or(0, |b| b + 1);
+            let split = (0..count)
+                .flat_map(|b| (0..columns).map(move |c| (b, c)))
+                .find(|(b, c)| {
+                    (0..block.len())
+                        .filter(|s| block[*s] == *b)
+                        .map(|s| block[self.moves[s][*c]])
+                        .collect::<BTreeSet<_>>()
+                        .len()
+                        > 1
+                });
+            let Some((b, c)) = split else {
+                return steps;
+            };
+            let mut parts: BTreeMap<usize, usize> = BTreeMap::new();
+            let next: Vec<usize> = (0..block.len())
+                .map(|s| match block[s] == b {
+                    true => {
+                        let part = parts.len();
+                        match *parts.entry(block[self.moves[s][c]]).or_insert(part) {
+                            0 => b,
+                            part => count + part - 1,
+                        }
+                    }
+                    false => block[s],
+                })
+                .collect();
+            steps.push((canonical(&next), Some((b, c))));
+        }
+    }
+
+    /// Shortest string from the first row to every state, in BFS order
+    fn access(&self, alphabet: &[char]) -> Vec<(usize, String)> {
+        let mut words: Vec<(usize, String)> = Vec::new();
+        let mut seen = BTreeSet::from([0]);
+        let mut queue: VecDeque<(usize, String)> = match self.ids.is_empty() {
+            true => VecDeque::new(),
+            false => VecDeque::from([(0, String::new())]),
+        };
+        while let Some((state, word)) = queue.pop_front() {
+            for (column, c) in alphabet.iter().enumerate() {
+                let next = self.moves[state][column];
+                if seen.insert(next) {
+                    queue.push_back((next, format!("{}{}", word, c)));
+                }
+            }
+            words.push((state, word));
+        }
+        words
+    }
+
+    /// Shortest string accepted from exactly one of `a` and `b`
+    fn distinguish(&self, a: usize, b: usize, alphabet: &[char]) -> Option<String> {
+        let mut parents: BTreeMap<Pair, Option<(Pair, char)>> = BTreeMap::from([((a, b), None)]);
+        let mut queue = VecDeque::from([(a, b)]);
+        while let Some(pair) = queue.pop_front() {
+            if self.accepting[pair.0] != self.accepting[pair.1] {
+                let mut word = Vec::new();
+                let mut current = pair;
+                while let Some((parent, c)) = parents[&current] {
+                    word.push(c);
+                    current = parent;
+                }
+                return Some(word.into_iter().rev().collect());
+            }
+            for (column, c) in alphabet.iter().enumerate() {
+                let next = (self.moves[pair.0][column], self.moves[pair.1][column]);
+                if let Entry::Vacant(entry) = parents.entry(next) {
+                    entry.insert(Some((pair, *c)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Renumbers blocks in the order of their first state
+fn canonical(block: &[usize]) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::new();
+    block
+        .iter()
+        .map(|b| match order.iter().position(|o| o == b) {
+            Some(i) => i,
+            None => {
+                order.push(*b);
+                order.len() - 1
+            }
+        })
+        .collect()
+}
+
+/// One round of partition refinement
+pub struct PartitionStep {
+    /// The current partition, blocks in the order of their first state in the table
+    pub blocks: Vec<Vec<State>>,
+    /// The block of the previous step that was split and the symbol telling its states apart,
+    /// `None` for the initial split into accepting and rejecting states
+    pub splitter: Option<(usize, char)>,
+}
+
+/// Every round of the refinement [`classes`] does, the last one being the Myhill–Nerode classes.
+/// Rounds that only set apart the dead state missing transitions lead to are left out, that
+/// state being last keeps the numbering of the other blocks the same.
+pub fn refinement(table: &LabeledTable) -> Vec<PartitionStep> {
+    let complete = Complete::new(table);
+    let mut steps: Vec<PartitionStep> = Vec::new();
+    for (block, splitter) in complete.refine() {
+        let mut blocks: Vec<Vec<State>> = Vec::new();
+        for (i, id) in complete.ids.iter().enumerate() {
+            match blocks.get_mut(block[i]) {
+                Some(members) => members.push(*id),
+                None => blocks.push(vec![*id]),
+            }
+        }
+        if steps.last().is_none_or(|last| last.blocks != blocks) {
+            steps.push(PartitionStep {
+                blocks,
+                splitter: splitter.map(|(b, c)| (b, table.alphabet()[c])),
+            });
+        }
+    }
+    steps
+}
+
+/// The minimal DFA of `table` by partition refinement, one row per Myhill–Nerode class numbered
+/// in the order of its first state. The class of states that cannot reach an accepting state is
+/// left out like missing transitions are, unless the start state is in it.
+pub(crate) fn quotient(table: &LabeledTable) -> LabeledTable {
+    let complete = Complete::new(table);
+    let (block, _) = complete
+        .refine()
+        .pop()
+        .expect("starts with the initial split");
+    let dead = block[complete.ids.len()];
+    let count = match complete.ids.is_empty() {
+        true => 0,
+        false => block.iter().max().map_or(0, |b| b + 1),
+    };
+    let mut next = 0;
+    let ids: Vec<Option<State>> = (0..count)
+        .map(|b| {
+            (b != dead || b == block[0]).then(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect();
+
+    let rows = (0..count)
+        .filter_map(|b| {
+            let id = ids[b]?;
+            let state = block
+                .iter()
+                .position(|s| *s == b)
+                .expect("blocks have states");
+            Some(TransitionTableRow {
+                id,
+                accepting: complete.accepting[state],
+                transitions: complete.moves[state]
+                    .iter()
+                    .map(|t| ids[block[*t]].filter(|_| block[*t] != dead))
+                    .collect(),
+            })
+        })
+        .collect();
+    let mut quotient = LabeledTable::new(table.alphabet().to_vec(), TransitionTable { rows })
+        .expect("rows have one column per alphabet symbol");
+    quotient.tokens = table.tokens.clone();
+    quotient
+}
+
+/// Groups the states of `table` into their Myhill–Nerode classes. States that cannot reach an
+/// accepting state share a class with the missing transitions, so they are grouped together even
+/// if the table has no explicit dead state.
+pub fn classes(table: &LabeledTable) -> Classes {
+    let complete = Complete::new(table);
+    let (block, _) = complete
+        .refine()
+        .pop()
+        .expect("starts with the initial split");
+
+    let mut order: Vec<usize> = Vec::new();
+    let mut classes: Vec<Class> = Vec::new();
+    for (i, id) in complete.ids.iter().enumerate() {
+        let index = match order.iter().position(|b| *b == block[i]) {
+            Some(index) => index,
+            None => {
+                order.push(block[i]);
+                classes.push(Class {
+                    states: Vec::new(),
+                    accepting: complete.accepting[i],
+                    access: None,
+                });
+                classes.len() - 1
+            }
+        };
+        classes[index].states.push(*id);
+    }
+    // The first state of a class reached in BFS order has its shortest access string
+    for (state, word) in complete.access(table.alphabet()) {
+        if let Some(index) = order.iter().position(|b| *b == block[state]) {
+            classes[index].access.get_or_insert(word);
+        }
+    }
+
+    let representative = |index: usize| {
+        complete
+            .ids
+            .iter()
+            .position(|id| *id == classes[index].states[0])
+            .expect("class members are rows")
+    };
+    let mut distinguishing = BTreeMap::new();
+    for i in 0..classes.len() {
+        for j in i + 1..classes.len() {
+            if let Some(word) =
+                complete.distinguish(representative(i), representative(j), table.alphabet())
+            {
+                distinguishing.insert((i, j), word);
+            }
+        }
+    }
+
+    Classes {
+        classes,
+        distinguishing,
+        names: complete
+            .ids
+            .iter()
+            .map(|id| (*id, table.state_name(*id)))
+            .collect(),
+    }
+}
+
+fn word(word: &str) -> String {
+    match word.is_empty() {
+        true => Transition::Lambda.to_string(),
+        false => word
+            .chars()
+            .map(|c| Transition::Char(c).to_string())
+            .collect(),
+    }
+}
+
+impl Classes {
+    /// Index of the class `state` belongs to
+    pub fn class_of(&self, state: State) -> Option<usize> {
+        self.classes.iter().position(|c| c.states.contains(&state))
+    }
+
+    /// The DFA with the states of every class boxed in together, labeled with the class's access
+    /// string
+    pub fn to_digraph(&self, table: &LabeledTable) -> Digraph {
+        let mut graph = Digraph::from(table);
+        graph.clusters = self
+            .classes
+            .iter()
+            .enumerate()
+            .map(|(i, class)| {
+                let label = match &class.access {
+                    Some(access) => format!("[{}]", word(access)),
+                    None => format!("class {}", i),
+                };
+                (label, class.states.iter().copied().collect())
+            })
+            .collect();
+        graph
+    }
+}
+
+impl Display for Classes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, class) in self.classes.iter().enumerate() {
+            let states: Vec<&str> = class
+                .states
+                .iter()
+                .map(|s| self.names[s].as_str())
+                .collect();
+            let access = class
+                .access
+                .as_ref()
+                .map_or("unreachable".to_owned(), |a| format!("{:?}", a));
+            writeln!(
+                f,
+                "{} {}: {} (access {})",
+                if class.accepting { "+" } else { "-" },
+                i,
+                states.join(" "),
+                access
+            )?;
+        }
+        for ((i, j), word) in &self.distinguishing {
+            writeln!(f, "{} vs {}: {:?}", i, j, word)?;
+        }
+        Ok(())
+    }
+}
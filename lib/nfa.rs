@@ -1,13 +1,38 @@
-use alphabet_encoding::{decode, encode};
-use std::error::Error;
+use alphabet_encoding::encode;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    str::FromStr,
+use transition_tables::{TransitionTable, TransitionTableRow};
+
+use crate::{
+    LabeledTable,
+    digraph::escape,
+    table::column_symbol,
+    tokens::{self, Tokens},
+    validate::debug_validate,
 };
 
-type State = usize;
-type Transitions = BTreeMap<Transition, BTreeSet<State>>;
+mod dense;
+mod language;
+mod lazy;
+mod limits;
+mod parse;
+mod search;
+#[cfg(feature = "test-util")]
+pub mod testing;
+mod transform;
+mod worksheet;
+
+use dense::Dense;
+pub use lazy::LazyDfa;
+use limits::Budget;
+pub use limits::{LimitExceeded, Limits, Progress, Stage};
+pub(crate) use parse::directive;
+pub use parse::{ParseError, ParseErrorKind};
+pub use transform::Minimizer;
+pub use worksheet::{Worksheet, WorksheetRow};
+
+pub(crate) type State = usize;
+pub(crate) type Transitions = BTreeMap<Transition, BTreeSet<State>>;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Transition {
@@ -15,149 +40,371 @@ pub enum Transition {
     Lambda,
 }
 
-impl Display for Transition {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Transition::Char(c) if *c == ' ' => "SP".to_string(),
+/// Whitespace symbols and the names they are written as, since they would otherwise vanish
+/// between whitespace-separated fields
+const NAMED_SYMBOLS: [(char, &str); 4] = [(' ', "SP"), ('\t', "TAB"), ('\n', "NL"), ('\r', "CR")];
+
+/// The name `c` is written as, if it has one
+pub(crate) fn symbol_name(c: char) -> Option<&'static str> {
+    NAMED_SYMBOLS.iter().find(|(s, _)| *s == c).map(|(_, n)| *n)
+}
+
+/// The symbol `name` stands for, if it is one of the names
+pub(crate) fn named_symbol(name: &str) -> Option<char> {
+    NAMED_SYMBOLS
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(s, _)| *s)
+}
+
+/// How transition labels are spelled for an output target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelStyle {
+    pub lambda: String,
+    /// Escape symbols for HTML-like labels, `lambda` is written as is
+    pub html: bool,
+}
+
+impl LabelStyle {
+    /// Graphviz HTML-like labels, lambda as `&lambda;`
+    pub fn dot() -> Self {
+        Self {
+            lambda: "&lambda;".to_owned(),
+            html: true,
+        }
+    }
+
+    /// Plain text and Mermaid, lambda as `λ`
+    pub fn text() -> Self {
+        Self {
+            lambda: "λ".to_owned(),
+            html: false,
+        }
+    }
+
+    pub fn with_lambda(mut self, lambda: impl Into<String>) -> Self {
+        self.lambda = lambda.into();
+        self
+    }
+}
+
+impl Default for LabelStyle {
+    fn default() -> Self {
+        Self::dot()
+    }
+}
+
+impl Transition {
+    pub fn render(&self, style: &LabelStyle) -> String {
+        let symbol = match self {
+            // Italic in HTML-like labels to set names apart from symbols
+            Transition::Char(c) if let Some(name) = symbol_name(*c) => {
+                return match style.html {
+                    true => format!("<i>{}</i>", name),
+                    false => name.to_owned(),
+                };
+            }
+            // Edge labels are split on these, see `import::parse_label`
+            Transition::Char(c @ ('|' | ',')) if style.html => return format!("&#{};", *c as u32),
             Transition::Char(c) if c.is_ascii_graphic() => c.to_string(),
             Transition::Char(c) => encode(c.to_string()),
-            Transition::Lambda => "&lambda;".to_string(),
+            Transition::Lambda => return style.lambda.clone(),
         };
+        match style.html {
+            true => escape(&symbol),
+            false => symbol,
+        }
+    }
+}
 
-        write!(f, "{}", s)
+/// Spelled for DOT, see [`LabelStyle::dot`]
+impl Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(&LabelStyle::dot()))
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NFA {
     states: BTreeMap<State, (bool, Transitions)>,
     /// Ordering should be preserved and used as the order in the output DFA
     alphabet: Vec<char>,
+    /// Names of states that were defined by name rather than number
+    labels: BTreeMap<State, String>,
+    /// Multi-character symbols, see [`NFA::tokens`]
+    tokens: Tokens,
 }
 
-#[derive(Debug)]
-pub enum ParseError {
-    EmptyFile,
-    InvalidFirstLine,
-    EmptyAlphabetChar,
-    ColumnMismatch,
-    EmptyTransition,
-    InvalidEncoding,
-    InvalidFromTo,
-}
-impl Error for ParseError {}
-impl Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let str = match *self {
-            ParseError::EmptyFile => "EmptyFile: File has no lines!",
-            ParseError::InvalidFirstLine => {
-                "InvalidFirstLine: Unable to parse first line of definition file!"
-            }
-            ParseError::EmptyAlphabetChar => {
-                "EmptyAlphabetChar: Alphabet character is empty string!"
-            }
-            ParseError::ColumnMismatch => "ColumnMismatch: Not enough columns in row!",
-            ParseError::EmptyTransition => "EmptyTransition: Transition character is empty string!",
-            ParseError::InvalidEncoding => "InvalidTransition: Decoding alphabet_encoding failed!",
-            ParseError::InvalidFromTo => "InvalidFromTo: Unable to parse from or to nodes!",
-        };
-        write!(f, "{}", str)
+impl NFA {
+    /// The lowest numbered state in `states` is the start state
+    pub(crate) fn from_parts(
+        states: BTreeMap<State, (bool, Transitions)>,
+        alphabet: Vec<char>,
+    ) -> Self {
+        Self {
+            states,
+            alphabet,
+            labels: BTreeMap::new(),
+            tokens: Tokens::new(),
+        }
+    }
+
+    /// Keeps `labels` as the original names of the states, see [`NFA::labels`]
+    pub(crate) fn with_labels(mut self, labels: BTreeMap<State, String>) -> Self {
+        self.labels = labels;
+        self
     }
-}
 
-impl NFA {
     pub fn states(&self) -> BTreeMap<State, (bool, Transitions)> {
         self.states.clone()
     }
-}
 
-impl FromStr for NFA {
-    type Err = ParseError;
+    /// Original names of states defined by name, numeric states have none
+    pub fn labels(&self) -> &BTreeMap<State, String> {
+        &self.labels
+    }
+
+    pub fn alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    /// Names of the symbols of more than one character, keyed by the private use character that
+    /// stands for each in the alphabet and transitions
+    pub fn tokens(&self) -> &BTreeMap<char, String> {
+        &self.tokens
+    }
+
+    /// The input standing for the token stream `input`, `None` if a token is neither a single
+    /// character nor one of [`NFA::tokens`]
+    pub fn spell(&self, input: &[&str]) -> Option<String> {
+        tokens::spell(&self.tokens, input)
+    }
+
+    /// The lowest numbered state
+    pub fn start(&self) -> Option<State> {
+        self.states.keys().next().copied()
+    }
+
+    pub fn is_accepting(&self, state: State) -> bool {
+        self.states
+            .get(&state)
+            .is_some_and(|(accepting, _)| *accepting)
+    }
+
+    fn targets(&self, state: State, transition: Transition) -> impl Iterator<Item = State> + '_ {
+        self.states
+            .get(&state)
+            .and_then(|(_, transitions)| transitions.get(&transition))
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// All states reachable from `states` using only lambda transitions
+    pub fn lambda_closure(&self, states: &BTreeSet<State>) -> BTreeSet<State> {
+        let mut closure = states.clone();
+        let mut stack: Vec<State> = states.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            for target in self.targets(state, Transition::Lambda) {
+                if closure.insert(target) {
+                    stack.push(target);
+                }
+            }
+        }
+        closure
+    }
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let line_fields: Vec<&str> = lines
-            .next()
-            .ok_or(ParseError::EmptyFile)?
-            .splitn(3, " ")
+    /// States reachable from `states` on `c`, including the lambda closure afterwards
+    pub fn step(&self, states: &BTreeSet<State>, c: char) -> BTreeSet<State> {
+        let moved = states
+            .iter()
+            .flat_map(|s| self.targets(*s, Transition::Char(c)))
             .collect();
-        let (lambda_char, alphabet) = (
-            *line_fields.get(1).ok_or(ParseError::InvalidFirstLine)?,
-            *line_fields.get(2).ok_or(ParseError::InvalidFirstLine)?,
-        );
-
-        // Ordering should be preserved and used as the order in the output DFA
-        let alphabet: Result<Vec<char>, ParseError> = decode(alphabet.to_string())
-            .map_err(|_| ParseError::InvalidEncoding)?
-            .split_whitespace()
-            .map(|s| s.chars().next().ok_or(ParseError::EmptyAlphabetChar))
+        self.lambda_closure(&moved)
+    }
+
+    /// Simulates the NFA on `input` without building a DFA
+    pub fn accepts(&self, input: &str) -> bool {
+        let dense = Dense::new(self);
+        let Some(mut current) = dense.start() else {
+            return false;
+        };
+        for c in input.chars() {
+            match self.alphabet.iter().position(|a| *a == c) {
+                Some(symbol) if !current.is_empty() => current = dense.step(&current, symbol),
+                _ => return false,
+            }
+        }
+        dense.accepts(&current)
+    }
+
+    /// [`NFA::accepts`] over a token stream, see [`NFA::spell`]
+    pub fn accepts_tokens(&self, input: &[&str]) -> bool {
+        self.spell(input).is_some_and(|input| self.accepts(&input))
+    }
+
+    /// Equivalent NFA without lambda transitions, over the same states.
+    /// A state moves on `c` wherever its lambda closure did, and accepts if its closure did.
+    pub fn remove_lambdas(&self) -> NFA {
+        let states = self
+            .states
+            .keys()
+            .map(|state| {
+                let closure = self.lambda_closure(&BTreeSet::from([*state]));
+                let accepting = closure.iter().any(|s| self.is_accepting(*s));
+                let mut transitions = Transitions::new();
+                for (t, targets) in closure
+                    .iter()
+                    .filter_map(|s| self.states.get(s))
+                    .flat_map(|(_, t)| t)
+                    .filter(|(t, _)| **t != Transition::Lambda)
+                {
+                    transitions.entry(*t).or_default().extend(targets);
+                }
+                (*state, (accepting, transitions))
+            })
             .collect();
 
-        type DefinitionRow = (bool, State, State, Vec<Transition>);
-        let rows: Result<Vec<DefinitionRow>, ParseError> = lines
-            .filter(|l| !l.is_empty())
-            .map(|l| {
-                l.split_whitespace()
-                    .filter(|s| !s.is_empty())
-                    .map(str::to_string)
-                    .reduce(|acc, s| acc + " " + &s)
-                    .unwrap()
+        let nfa = Self {
+            states,
+            alphabet: self.alphabet.clone(),
+            labels: self.labels.clone(),
+            tokens: self.tokens.clone(),
+        };
+        debug_validate(&nfa, "remove_lambdas");
+        nfa
+    }
+
+    /// Approximate bytes used, counting map entries but not B-tree node overhead
+    pub fn memory_footprint(&self) -> usize {
+        size_of::<Self>()
+            + self.alphabet.capacity() * size_of::<char>()
+            + self
+                .states
+                .values()
+                .map(|(_, transitions)| {
+                    size_of::<(State, (bool, Transitions))>()
+                        + transitions
+                            .values()
+                            .map(|targets| {
+                                size_of::<(Transition, BTreeSet<State>)>()
+                                    + targets.len() * size_of::<State>()
+                            })
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    /// No lambda transitions and at most one target per character
+    pub fn is_deterministic(&self) -> bool {
+        self.states.values().all(|(_, transitions)| {
+            transitions
+                .iter()
+                .all(|(t, targets)| *t != Transition::Lambda && targets.len() <= 1)
+        })
+    }
+
+    /// Columns follow the order of the alphabet. Returns `None` if the NFA is not deterministic.
+    pub fn to_table(&self) -> Option<LabeledTable> {
+        if !self.is_deterministic() {
+            return None;
+        }
+
+        let ids: BTreeSet<State> = self
+            .states
+            .iter()
+            .flat_map(|(s, (_, transitions))| {
+                std::iter::once(*s).chain(transitions.values().flatten().copied())
             })
-            .map(|l: String| {
-                let line_fields: Vec<_> = l.splitn(3, ' ').collect();
-                let (accepting, from, to_and_chars) = (
-                    *line_fields.first().ok_or(ParseError::ColumnMismatch)?,
-                    *line_fields.get(1).ok_or(ParseError::ColumnMismatch)?,
-                    *line_fields.get(2).ok_or(ParseError::ColumnMismatch)?,
-                );
-                let (to, chars) = to_and_chars
-                    .split_once(' ')
-                    .unwrap_or((to_and_chars, lambda_char));
-
-                let transitions: Result<Vec<_>, ParseError> = chars
-                    .to_string()
-                    .split_whitespace()
-                    .map(|c| decode(c.to_string()).map_err(|_| ParseError::InvalidEncoding))
-                    .map(|c| {
-                        Ok(match c? {
-                            a if a == lambda_char => Transition::Lambda,
-                            a => Transition::Char(
-                                a.chars().next().ok_or(ParseError::EmptyTransition)?,
-                            ),
+            .collect();
+        let rows = ids
+            .into_iter()
+            .map(|id| {
+                let (accepting, transitions) = self
+                    .states
+                    .get(&id)
+                    .map(|(a, t)| (*a, t.clone()))
+                    .unwrap_or_default();
+                TransitionTableRow {
+                    id,
+                    accepting,
+                    transitions: self
+                        .alphabet
+                        .iter()
+                        .map(|c| {
+                            transitions
+                                .get(&Transition::Char(*c))
+                                .and_then(|targets| targets.first().copied())
                         })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut table = LabeledTable::new(self.alphabet.clone(), TransitionTable { rows }).ok()?;
+        table.state_labels = self.labels.clone();
+        table.tokens = self.tokens.clone();
+        debug_validate(&table, "to_table");
+        Some(table)
+    }
+}
+
+impl NFA {
+    fn from_table(
+        table: &TransitionTable,
+        alphabet: Vec<char>,
+        labels: BTreeMap<State, String>,
+    ) -> Self {
+        let states = table
+            .rows
+            .iter()
+            .map(|row| {
+                let transitions: Transitions = row
+                    .transitions
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, t)| {
+                        t.map(|t| (Transition::Char(alphabet[i]), BTreeSet::from([t])))
                     })
                     .collect();
-
-                Ok((
-                    accepting == "+",
-                    from.parse::<usize>()
-                        .map_err(|_| ParseError::InvalidFromTo)?,
-                    to.parse::<usize>().map_err(|_| ParseError::InvalidFromTo)?,
-                    transitions?,
-                ))
+                (row.id, (row.accepting, transitions))
             })
             .collect();
 
-        let mut states: BTreeMap<State, (bool, Transitions)> = BTreeMap::new();
-        rows?.into_iter().for_each(|(accepting, from, to, chars)| {
-            states
-                .entry(from)
-                .or_insert((accepting, Transitions::default()))
-                .0 |= accepting;
-            chars.iter().for_each(|c| {
-                states
-                    .entry(from)
-                    .or_insert((accepting, Transitions::default()))
-                    .1
-                    .entry(*c)
-                    .or_default()
-                    .insert(to);
-            });
-        });
-
-        Ok(Self {
+        Self {
             states,
-            alphabet: alphabet?,
-        })
+            alphabet,
+            labels,
+            tokens: Tokens::new(),
+        }
+    }
+}
+
+impl From<&TransitionTable> for NFA {
+    fn from(value: &TransitionTable) -> Self {
+        let width = value
+            .rows
+            .iter()
+            .map(|r| r.transitions.len())
+            .max()
+            .unwrap_or_default();
+        NFA::from_table(
+            value,
+            (0..width).map(column_symbol).collect(),
+            BTreeMap::new(),
+        )
+    }
+}
+
+impl From<&LabeledTable> for NFA {
+    fn from(value: &LabeledTable) -> Self {
+        NFA {
+            tokens: value.tokens.clone(),
+            ..NFA::from_table(
+                value.table(),
+                value.alphabet().to_vec(),
+                value.state_labels.clone(),
+            )
+        }
     }
 }
@@ -0,0 +1,203 @@
+//! Vector indexed view of an NFA for the hot loops of simulation and subset construction
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{NFA, State, Transition};
+
+/// States are renumbered to their position in ascending id order and sets of them are sorted,
+/// deduplicated vectors. Built once per operation, the NFA itself keeps its maps.
+pub(super) struct Dense {
+    /// Original ids, indexed by dense state
+    pub(super) ids: Vec<State>,
+    accepting: Vec<bool>,
+    /// Targets of every dense state on every alphabet position, without lambda closure
+    moves: Vec<Vec<Vec<usize>>>,
+    /// Strongly connected component of every dense state in the lambda transition graph
+    component: Vec<usize>,
+    /// Lambda closure shared by the states of each component
+    closures: Vec<Vec<usize>>,
+}
+
+/// Lambda closures of all states at once. States on a lambda cycle share their closure, so
+/// Tarjan's algorithm groups them into components, and since it finishes every component
+/// reachable from another first, a component's closure is its members plus the already known
+/// closures of the components its lambda transitions lead to.
+fn closures(lambdas: &[Vec<usize>]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    const UNVISITED: usize = usize::MAX;
+    let n = lambdas.len();
+    let (mut index, mut low) = (vec![UNVISITED; n], vec![0; n]);
+    let mut on_stack = vec![false; n];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut component = vec![UNVISITED; n];
+    let mut closures: Vec<Vec<usize>> = Vec::new();
+    let mut next = 0;
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // Explicit call stack of (state, next lambda edge to follow)
+        let mut work = vec![(root, 0)];
+        (index[root], low[root], on_stack[root]) = (next, next, true);
+        stack.push(root);
+        next += 1;
+        while let Some((v, edge)) = work.last().copied() {
+            if let Some(&w) = lambdas[v].get(edge) {
+                work.last_mut().expect("v is on the work stack").1 += 1;
+                if index[w] == UNVISITED {
+                    (index[w], low[w], on_stack[w]) = (next, next, true);
+                    stack.push(w);
+                    next += 1;
+                    work.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let id = closures.len();
+                let mut closure = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component[w] = id;
+                    closure.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                let reached: Vec<usize> = closure
+                    .iter()
+                    .flat_map(|m| &lambdas[*m])
+                    .filter(|t| component[**t] != id)
+                    .flat_map(|t| &closures[component[*t]])
+                    .copied()
+                    .collect();
+                closure.extend(reached);
+                closure.sort_unstable();
+                closure.dedup();
+                closures.push(closure);
+            }
+        }
+    }
+    (component, closures)
+}
+
+impl Dense {
+    pub(super) fn new(nfa: &NFA) -> Self {
+        let ids: Vec<State> = nfa.states.keys().copied().collect();
+        let index: BTreeMap<State, usize> = ids.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+        let dense = |targets: Option<&BTreeSet<State>>| -> Vec<usize> {
+            targets
+                .into_iter()
+                .flatten()
+                .filter_map(|t| index.get(t).copied())
+                .collect()
+        };
+
+        let accepting = nfa
+            .states
+            .values()
+            .map(|(accepting, _)| *accepting)
+            .collect();
+        let moves = nfa
+            .states
+            .values()
+            .map(|(_, transitions)| {
+                nfa.alphabet
+                    .iter()
+                    .map(|c| dense(transitions.get(&Transition::Char(*c))))
+                    .collect()
+            })
+            .collect();
+        let lambdas: Vec<Vec<usize>> = nfa
+            .states
+            .values()
+            .map(|(_, transitions)| dense(transitions.get(&Transition::Lambda)))
+            .collect();
+
+        let (component, closures) = closures(&lambdas);
+
+        Self {
+            ids,
+            accepting,
+            moves,
+            component,
+            closures,
+        }
+    }
+
+    /// Lambda closure of the start state, `None` without states
+    pub(super) fn start(&self) -> Option<Vec<usize>> {
+        let component = self.component.first()?;
+        Some(self.closures[*component].clone())
+    }
+
+    /// Lambda closure of the NFA states `states`, ignoring ids that are not states
+    pub(super) fn closure(&self, states: &BTreeSet<State>) -> Vec<usize> {
+        let mut closure: Vec<usize> = states
+            .iter()
+            .filter_map(|s| self.ids.binary_search(s).ok())
+            .flat_map(|s| &self.closures[self.component[s]])
+            .copied()
+            .collect();
+        closure.sort_unstable();
+        closure.dedup();
+        closure
+    }
+
+    /// Class of every alphabet position and the first position of every class. Symbols are in
+    /// the same class when every state moves the same way on them, so stepping on one stands
+    /// for all. Classes are numbered in order of their first position.
+    pub(super) fn symbol_classes(&self, symbols: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut ids: BTreeMap<Vec<&Vec<usize>>, usize> = BTreeMap::new();
+        let mut first = Vec::new();
+        let class = (0..symbols)
+            .map(|symbol| {
+                let column = self.moves.iter().map(|m| &m[symbol]).collect();
+                *ids.entry(column).or_insert_with(|| {
+                    first.push(symbol);
+                    first.len() - 1
+                })
+            })
+            .collect();
+        (class, first)
+    }
+
+    pub(super) fn accepts(&self, set: &[usize]) -> bool {
+        set.iter().any(|s| self.accepting[*s])
+    }
+
+    /// Lambda closure of the states reached from `set` on the symbol at `symbol` in the alphabet.
+    /// A state already in the result brought its whole closure along, so its own is skipped.
+    /// Tarjan's algorithm numbers a component after every component it reaches, so going through
+    /// the targets by descending component expands the larger closures first.
+    pub(super) fn step(&self, set: &[usize], symbol: usize) -> Vec<usize> {
+        let mut targets: Vec<usize> = set
+            .iter()
+            .flat_map(|s| &self.moves[*s][symbol])
+            .copied()
+            .collect();
+        targets.sort_unstable_by_key(|t| Reverse(self.component[*t]));
+        let mut reached = vec![false; self.ids.len()];
+        let mut next = Vec::new();
+        for t in targets {
+            if reached[t] {
+                continue;
+            }
+            for s in &self.closures[self.component[t]] {
+                if !reached[*s] {
+                    reached[*s] = true;
+                    next.push(*s);
+                }
+            }
+        }
+        next.sort_unstable();
+        next
+    }
+}
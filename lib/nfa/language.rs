@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use super::{Budget, Dense, LimitExceeded, NFA, Stage, State, Transition};
+use crate::FiniteAutomaton;
+
+/// The reachable part of the subset construction, with the empty set as an explicit dead state
+pub(super) struct Subsets {
+    /// The NFA states making up each set, the start set first
+    pub(super) sets: Vec<BTreeSet<State>>,
+    pub(super) accepting: Vec<bool>,
+    /// `transitions[set][symbol]` in alphabet order
+    pub(super) transitions: Vec<Vec<usize>>,
+}
+
+impl Subsets {
+    fn reverse_reachable(&self, from: impl Iterator<Item = usize>) -> Vec<bool> {
+        let mut seen = vec![false; self.accepting.len()];
+        let mut stack: Vec<usize> = from.collect();
+        while let Some(n) = stack.pop() {
+            if std::mem::replace(&mut seen[n], true) {
+                continue;
+            }
+            stack.extend(
+                (0..self.transitions.len()).filter(|src| self.transitions[*src].contains(&n)),
+            );
+        }
+        seen
+    }
+}
+
+impl NFA {
+    fn start_set(&self) -> BTreeSet<State> {
+        self.lambda_closure(&self.start().into_iter().collect())
+    }
+
+    fn accepts_set(&self, states: &BTreeSet<State>) -> bool {
+        states.iter().any(|s| self.is_accepting(*s))
+    }
+
+    /// Accepted strings of at most `max_len` characters, shortest first and then in alphabet order.
+    /// Prefixes that cannot lead to acceptance are dropped, so a finite language ends the
+    /// iterator whatever `max_len` is.
+    pub fn enumerate_accepted(&self, max_len: usize) -> impl Iterator<Item = String> + '_ {
+        let live = self.live_states();
+        let is_live = move |states: &BTreeSet<State>| states.iter().any(|s| live.contains(s));
+        let start = self.start_set();
+        let mut queue = VecDeque::new();
+        if is_live(&start) {
+            queue.push_back((String::new(), start));
+        }
+        std::iter::from_fn(move || {
+            while let Some((word, states)) = queue.pop_front() {
+                if word.chars().count() < max_len {
+                    for c in &self.alphabet {
+                        let next = self.step(&states, *c);
+                        if is_live(&next) {
+                            queue.push_back((format!("{}{}", word, c), next));
+                        }
+                    }
+                }
+                if self.accepts_set(&states) {
+                    return Some(word);
+                }
+            }
+            None
+        })
+    }
+
+    /// Breadth-first search over reachable state sets for the first one satisfying `goal`
+    fn shortest_word(&self, goal: impl Fn(&BTreeSet<State>) -> bool) -> Option<String> {
+        let start = self.start_set();
+        let mut seen = BTreeSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(String::new(), start)]);
+        while let Some((word, states)) = queue.pop_front() {
+            if goal(&states) {
+                return Some(word);
+            }
+            for c in &self.alphabet {
+                let next = self.step(&states, *c);
+                if seen.insert(next.clone()) {
+                    queue.push_back((format!("{}{}", word, c), next));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn shortest_accepted(&self) -> Option<String> {
+        self.shortest_word(|states| self.accepts_set(states))
+    }
+
+    /// Shortest string over the alphabet that is not accepted, `None` if every string is
+    pub fn shortest_rejected(&self) -> Option<String> {
+        self.shortest_word(|states| !self.accepts_set(states))
+    }
+
+    pub(super) fn subsets(&self) -> Subsets {
+        let start = self.start().into_iter().collect();
+        self.subsets_within(&Budget::unlimited(), Stage::Determinize, &start)
+            .expect("nothing is limited")
+    }
+
+    /// Runs on the [`Dense`] view, every lambda closure is computed once up front and every set
+    /// is stepped once per class of symbols, see [`NFA::symbol_classes`]. The first set is the
+    /// lambda closure of `start`. `budget` is checked and told about `stage` after every set.
+    pub(super) fn subsets_within(
+        &self,
+        budget: &Budget,
+        stage: Stage,
+        start: &BTreeSet<State>,
+    ) -> Result<Subsets, LimitExceeded> {
+        let dense = Dense::new(self);
+        let (class, first) = dense.symbol_classes(self.alphabet.len());
+        let start = dense.closure(start);
+        let mut ids: BTreeMap<Vec<usize>, usize> = BTreeMap::from([(start.clone(), 0)]);
+        let mut sets: Vec<Vec<usize>> = vec![start];
+        let (mut accepting, mut transitions) = (Vec::new(), Vec::new());
+        // Non-empty sets found and bytes of the sets and rows so far
+        let (mut found, mut memory) = (usize::from(!sets[0].is_empty()), 0);
+        let mut i = 0;
+        while i < sets.len() {
+            let targets: Vec<usize> = first
+                .iter()
+                .map(|symbol| {
+                    let next = dense.step(&sets[i], *symbol);
+                    *ids.entry(next.clone()).or_insert_with(|| {
+                        found += usize::from(!next.is_empty());
+                        memory += 2 * next.len() * size_of::<usize>();
+                        sets.push(next);
+                        sets.len() - 1
+                    })
+                })
+                .collect();
+            let row = class.iter().map(|c| targets[*c]).collect();
+            accepting.push(dense.accepts(&sets[i]));
+            transitions.push(row);
+            memory += self.alphabet.len() * size_of::<usize>();
+            budget.report(stage, i + 1, sets.len());
+            budget.check(found, memory)?;
+            i += 1;
+        }
+        Ok(Subsets {
+            sets: sets
+                .into_iter()
+                .map(|set| set.into_iter().map(|s| dense.ids[s]).collect())
+                .collect(),
+            accepting,
+            transitions,
+        })
+    }
+
+    /// The alphabet split into groups of symbols that every state moves the same way on, in
+    /// order of their first symbol. Automata over large alphabets tend to have few of them.
+    pub fn symbol_classes(&self) -> Vec<Vec<char>> {
+        let (class, first) = Dense::new(self).symbol_classes(self.alphabet.len());
+        let mut classes = vec![Vec::new(); first.len()];
+        for (c, symbol) in class.iter().zip(&self.alphabet) {
+            classes[*c].push(*symbol);
+        }
+        classes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.subsets().accepting.contains(&true)
+    }
+
+    /// Whether the language has finitely many strings, i.e. no cycle can lead to acceptance
+    pub fn is_finite(&self) -> bool {
+        let subsets = self.subsets();
+        let live = subsets
+            .reverse_reachable((0..subsets.accepting.len()).filter(|s| subsets.accepting[*s]));
+
+        // Iterative DFS over live sets, a back edge means an infinite language
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Active,
+            Done,
+        }
+        let mut marks = vec![Mark::New; live.len()];
+        for root in (0..live.len()).filter(|s| live[*s]) {
+            if marks[root] != Mark::New {
+                continue;
+            }
+            let mut stack = vec![(root, 0)];
+            marks[root] = Mark::Active;
+            while let Some((node, edge)) = stack.pop() {
+                let Some(&next) = subsets.transitions[node].get(edge) else {
+                    marks[node] = Mark::Done;
+                    continue;
+                };
+                stack.push((node, edge + 1));
+                if !live[next] {
+                    continue;
+                }
+                match marks[next] {
+                    Mark::Active => return false,
+                    Mark::New => {
+                        marks[next] = Mark::Active;
+                        stack.push((next, 0));
+                    }
+                    Mark::Done => {}
+                }
+            }
+        }
+        true
+    }
+
+    /// Number of accepted strings of exactly `n` characters, saturating at `u128::MAX`
+    pub fn count_words_of_length(&self, n: usize) -> u128 {
+        let subsets = self.subsets();
+        let mut counts = vec![0u128; subsets.accepting.len()];
+        counts[0] = 1;
+        for _ in 0..n {
+            let mut next = vec![0u128; counts.len()];
+            for (set, count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+                for target in &subsets.transitions[set] {
+                    next[*target] = next[*target].saturating_add(*count);
+                }
+            }
+            counts = next;
+        }
+        counts
+            .iter()
+            .zip(&subsets.accepting)
+            .filter(|(_, accepting)| **accepting)
+            .fold(0u128, |acc, (count, _)| acc.saturating_add(*count))
+    }
+
+    /// Shortest word reaching each reachable state, ties broken by alphabet order
+    pub fn state_names(&self) -> BTreeMap<State, String> {
+        let mut names = BTreeMap::new();
+        let Some(start) = self.start() else {
+            return names;
+        };
+
+        // Lambda edges cost nothing, so they go to the front of the queue
+        let mut queue = VecDeque::from([(start, String::new())]);
+        while let Some((state, word)) = queue.pop_front() {
+            if names.contains_key(&state) {
+                continue;
+            }
+            for target in self.targets(state, Transition::Lambda) {
+                queue.push_front((target, word.clone()));
+            }
+            for c in &self.alphabet {
+                for target in self.targets(state, Transition::Char(*c)) {
+                    queue.push_back((target, format!("{}{}", word, c)));
+                }
+            }
+            names.insert(state, word);
+        }
+        names
+    }
+
+    /// Shortest string accepted by exactly one of the two automata, `None` if they are equivalent
+    pub fn distinguishing_string(&self, other: &NFA) -> Option<String> {
+        let alphabet: BTreeSet<char> = self
+            .alphabet
+            .iter()
+            .chain(&other.alphabet)
+            .copied()
+            .collect();
+        let start = (self.start_set(), other.start_set());
+        let mut seen = BTreeSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(String::new(), start)]);
+        while let Some((word, (a, b))) = queue.pop_front() {
+            if self.accepts_set(&a) != other.accepts_set(&b) {
+                return Some(word);
+            }
+            for c in &alphabet {
+                let next = (self.step(&a, *c), other.step(&b, *c));
+                if seen.insert(next.clone()) {
+                    queue.push_back((format!("{}{}", word, c), next));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn is_equivalent(&self, other: &NFA) -> bool {
+        self.distinguishing_string(other).is_none()
+    }
+}
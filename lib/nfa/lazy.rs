@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use super::{Dense, NFA};
+
+/// The subset construction done on demand while matching: a DFA state is built the first time
+/// a run reaches it and its transitions the first time they are taken, so memory grows with the
+/// states actually visited rather than every reachable one.
+///
+/// States are numbered in the order they are discovered, the start state first.
+pub struct LazyDfa {
+    dense: Dense,
+    /// Alphabet position of every symbol
+    columns: BTreeMap<char, usize>,
+    /// Symbol class of every alphabet position and the first position of every class
+    class: Vec<usize>,
+    first: Vec<usize>,
+    ids: BTreeMap<Vec<usize>, usize>,
+    sets: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+    /// Target of every state on every class, `None` until taken and `Some(None)` into the
+    /// empty set
+    transitions: Vec<Vec<Option<Option<usize>>>>,
+}
+
+impl LazyDfa {
+    pub fn new(nfa: &NFA) -> Self {
+        let dense = Dense::new(nfa);
+        let (class, first) = dense.symbol_classes(nfa.alphabet.len());
+        let mut dfa = Self {
+            columns: nfa
+                .alphabet
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (*c, i))
+                .collect(),
+            class,
+            first,
+            ids: BTreeMap::new(),
+            sets: Vec::new(),
+            accepting: Vec::new(),
+            transitions: Vec::new(),
+            dense,
+        };
+        if let Some(start) = dfa.dense.start() {
+            dfa.state(start);
+        }
+        dfa
+    }
+
+    /// Number of the state for `set`, adding it if it is new
+    fn state(&mut self, set: Vec<usize>) -> usize {
+        if let Some(id) = self.ids.get(&set) {
+            return *id;
+        }
+        let id = self.sets.len();
+        self.accepting.push(self.dense.accepts(&set));
+        self.transitions.push(vec![None; self.first.len()]);
+        self.ids.insert(set.clone(), id);
+        self.sets.push(set);
+        id
+    }
+
+    /// State 0, `None` for an NFA without states
+    pub fn start(&self) -> Option<usize> {
+        (!self.sets.is_empty()).then_some(0)
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting.get(state).copied().unwrap_or(false)
+    }
+
+    /// States discovered so far
+    pub fn state_count(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// The state `state` moves to on `c`, building it if needed. `None` if `c` is outside the
+    /// alphabet or no NFA state is left.
+    pub fn step(&mut self, state: usize, c: char) -> Option<usize> {
+        let class = self.class[*self.columns.get(&c)?];
+        if let Some(next) = self.transitions.get(state)?[class] {
+            return next;
+        }
+        let set = self.dense.step(&self.sets[state], self.first[class]);
+        let next = (!set.is_empty()).then(|| self.state(set));
+        self.transitions[state][class] = Some(next);
+        next
+    }
+
+    pub fn accepts(&mut self, input: &str) -> bool {
+        let mut current = self.start();
+        for c in input.chars() {
+            current = current.and_then(|s| self.step(s, c));
+        }
+        current.is_some_and(|s| self.is_accepting(s))
+    }
+
+    /// End of the longest non-empty match starting at `haystack[start]`, if any
+    fn longest_match_at(&mut self, haystack: &[char], start: usize) -> Option<usize> {
+        let mut current = self.start()?;
+        let mut end = None;
+        for (i, c) in haystack.iter().enumerate().skip(start) {
+            let Some(next) = self.step(current, *c) else {
+                break;
+            };
+            current = next;
+            if self.is_accepting(current) {
+                end = Some(i + 1);
+            }
+        }
+        end
+    }
+
+    /// Leftmost-longest matches anywhere in `haystack`, see [`NFA::find_matches`]
+    pub fn find_matches(&mut self, haystack: &[char], overlapping: bool) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start < haystack.len() {
+            match self.longest_match_at(haystack, start) {
+                Some(end) => {
+                    matches.push(start..end);
+                    start = if overlapping { start + 1 } else { end };
+                }
+                None => start += 1,
+            }
+        }
+        matches
+    }
+}
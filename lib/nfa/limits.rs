@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// Bounds on the work of a subset construction, all unlimited by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Most DFA states to build, not counting the empty set
+    pub max_states: Option<usize>,
+    /// Rough bound in bytes on the sets and transitions built
+    pub max_memory: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub enum LimitExceeded {
+    States(usize),
+    Memory(usize),
+    Timeout(Duration),
+}
+impl Error for LimitExceeded {}
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::States(n) => {
+                write!(f, "States: Subset construction passed {} states!", n)
+            }
+            LimitExceeded::Memory(n) => {
+                write!(f, "Memory: Subset construction passed {} bytes!", n)
+            }
+            LimitExceeded::Timeout(t) => {
+                write!(f, "Timeout: Subset construction ran longer than {:?}!", t)
+            }
+        }
+    }
+}
+
+/// Which subset construction a [`Progress`] report is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Determinize,
+    /// The first of Brzozowski's two determinizations, of the reversed NFA
+    MinimizeReversed,
+    /// The second, giving the minimal DFA
+    Minimize,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Determinize => write!(f, "determinizing"),
+            Stage::MinimizeReversed => write!(f, "minimizing (1/2)"),
+            Stage::Minimize => write!(f, "minimizing (2/2)"),
+        }
+    }
+}
+
+/// Told about every set a subset construction finishes: `done` of the `total` found so far. The
+/// total grows until the construction catches up with it.
+pub trait Progress {
+    fn on_progress(&self, stage: Stage, done: usize, total: usize);
+}
+
+/// Reports nowhere
+impl Progress for () {
+    fn on_progress(&self, _: Stage, _: usize, _: usize) {}
+}
+
+impl<F: Fn(Stage, usize, usize)> Progress for F {
+    fn on_progress(&self, stage: Stage, done: usize, total: usize) {
+        self(stage, done, total)
+    }
+}
+
+/// [`Limits`] with the clock started and where to report, shared by every construction of one
+/// operation
+pub(super) struct Budget<'a> {
+    limits: Limits,
+    deadline: Option<Instant>,
+    progress: &'a dyn Progress,
+}
+
+impl<'a> Budget<'a> {
+    pub(super) fn new(limits: &Limits, progress: &'a dyn Progress) -> Self {
+        Self {
+            limits: *limits,
+            deadline: limits.timeout.map(|t| Instant::now() + t),
+            progress,
+        }
+    }
+
+    pub(super) fn unlimited() -> Self {
+        Self::new(&Limits::default(), &())
+    }
+
+    pub(super) fn report(&self, stage: Stage, done: usize, total: usize) {
+        self.progress.on_progress(stage, done, total);
+    }
+
+    /// Fails once `states` or `memory` passes its limit or the time is up
+    pub(super) fn check(&self, states: usize, memory: usize) -> Result<(), LimitExceeded> {
+        if let Some(max) = self.limits.max_states.filter(|max| states > *max) {
+            return Err(LimitExceeded::States(max));
+        }
+        if let Some(max) = self.limits.max_memory.filter(|max| memory > *max) {
+            return Err(LimitExceeded::Memory(max));
+        }
+        match (self.deadline, self.limits.timeout) {
+            (Some(deadline), Some(timeout)) if Instant::now() > deadline => {
+                Err(LimitExceeded::Timeout(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use alphabet_encoding::{decode, encode};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::{NFA, State, Transition, Transitions, named_symbol, symbol_name};
+use crate::import::{self, assign_ids, labels};
+use crate::tokens::{self, Tokens};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    EmptyFile,
+    InvalidFirstLine,
+    EmptyAlphabetChar,
+    ColumnMismatch,
+    EmptyTransition,
+    InvalidEncoding,
+    MissingHeader,
+    UnknownStart,
+}
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match *self {
+            ParseErrorKind::EmptyFile => "EmptyFile: File has no lines!",
+            ParseErrorKind::InvalidFirstLine => {
+                "InvalidFirstLine: Unable to parse first line of definition file!"
+            }
+            ParseErrorKind::EmptyAlphabetChar => {
+                "EmptyAlphabetChar: Alphabet character is empty string!"
+            }
+            ParseErrorKind::ColumnMismatch => "ColumnMismatch: Not enough columns in row!",
+            ParseErrorKind::EmptyTransition => {
+                "EmptyTransition: Transition character is empty string!"
+            }
+            ParseErrorKind::InvalidEncoding => {
+                "InvalidTransition: Decoding alphabet_encoding failed!"
+            }
+            ParseErrorKind::MissingHeader => {
+                "MissingHeader: Expected a positional first line or lambda: and alphabet: directives!"
+            }
+            ParseErrorKind::UnknownStart => "UnknownStart: The start: directive names no state!",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// A [`ParseErrorKind`] and where it happened, lines and columns count from 1
+#[derive(Debug)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+    /// The whole offending line
+    pub text: String,
+}
+impl Error for ParseError {}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {}: {:?})",
+            self.kind, self.line, self.column, self.text
+        )
+    }
+}
+
+/// A line of the file with its 1-based number
+#[derive(Copy, Clone)]
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn error(&self, kind: ParseErrorKind, column: usize) -> ParseError {
+        ParseError {
+            kind,
+            line: self.number,
+            column,
+            text: self.text.to_owned(),
+        }
+    }
+
+    /// Whitespace separated fields with their 1-based column
+    fn fields(&self) -> Vec<(usize, &'a str)> {
+        let mut fields = Vec::new();
+        let mut start: Option<(usize, usize)> = None;
+        for (column, (i, c)) in self.text.char_indices().enumerate() {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some((column + 1, i)),
+                (true, Some((column, from))) => {
+                    fields.push((column, &self.text[from..i]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some((column, from)) = start {
+            fields.push((column, &self.text[from..]));
+        }
+        fields
+    }
+
+    /// Column just past the end, where a missing field would have been
+    fn end(&self) -> usize {
+        self.text.chars().count() + 1
+    }
+}
+
+/// `key: value` lines at the top of a definition file
+pub(crate) fn directive(line: &str) -> Option<(&str, &str)> {
+    import::directive(line, &["start", "lambda", "alphabet"])
+}
+
+type DefinitionRow = (bool, String, String, Vec<Transition>);
+
+/// Symbols of more than one character are interned into `tokens`, which is left as it was if
+/// the row fails to parse
+fn parse_row(
+    line: Line,
+    lambda_char: &str,
+    tokens: &mut Tokens,
+) -> Result<DefinitionRow, ParseError> {
+    let fields = line.fields();
+    let [(_, accepting), (_, from), (_, to), chars @ ..] = fields.as_slice() else {
+        return Err(line.error(ParseErrorKind::ColumnMismatch, line.end()));
+    };
+    let mut interned = tokens.clone();
+    let transitions = match chars {
+        [] => vec![Transition::Lambda],
+        chars => chars
+            .iter()
+            .map(|(column, c)| {
+                if let Some(c) = named_symbol(c) {
+                    return Ok(Transition::Char(c));
+                }
+                let a = decode(c.to_string())
+                    .map_err(|_| line.error(ParseErrorKind::InvalidEncoding, *column))?;
+                Ok(match a {
+                    a if a == lambda_char => Transition::Lambda,
+                    a => Transition::Char(
+                        tokens::intern(&mut interned, &a)
+                            .ok_or(line.error(ParseErrorKind::EmptyTransition, *column))?,
+                    ),
+                })
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?,
+    };
+    *tokens = interned;
+    Ok((
+        *accepting == "+",
+        from.to_string(),
+        to.to_string(),
+        transitions,
+    ))
+}
+
+impl NFA {
+    /// Writes the definition format in canonical form: one row per pair of states with symbols
+    /// in alphabet order, sorted by state, and a `start:` directive if states are named.
+    /// Accepting or start states without transitions get a lambda self-loop to keep them.
+    pub fn serialize(&self) -> String {
+        let lambda = ["&", "#", "~", "λ", "ε"]
+            .into_iter()
+            .find(|l| !self.alphabet.iter().any(|c| c.to_string() == *l))
+            .unwrap_or("&");
+        let name = |s: &State| match self.labels.get(s) {
+            Some(label) => label.split_whitespace().collect::<Vec<_>>().join("_"),
+            None => s.to_string(),
+        };
+        let encoded = |c: &char| match symbol_name(*c) {
+            Some(name) => name.to_owned(),
+            None => encode(self.tokens.get(c).cloned().unwrap_or(c.to_string())),
+        };
+        let symbol = |t: &Transition| match t {
+            Transition::Char(c) => encoded(c),
+            Transition::Lambda => lambda.to_owned(),
+        };
+        let order = |t: &Transition| match t {
+            Transition::Char(c) => self.alphabet.iter().position(|a| a == c),
+            Transition::Lambda => None,
+        };
+
+        let mut out = String::new();
+        if let Some(start) = self.start().filter(|_| !self.labels.is_empty()) {
+            out += &format!("start: {}\n", name(&start));
+        }
+        out += &[self.states.len().to_string(), lambda.to_owned()]
+            .into_iter()
+            .chain(self.alphabet.iter().map(encoded))
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push('\n');
+
+        for (from, (accepting, transitions)) in &self.states {
+            let marker = if *accepting { "+" } else { "-" };
+            let mut rows: BTreeMap<State, Vec<Transition>> = BTreeMap::new();
+            for (t, targets) in transitions {
+                for to in targets {
+                    rows.entry(*to).or_default().push(*t);
+                }
+            }
+            if rows.is_empty() && (*accepting || self.start() == Some(*from)) {
+                rows.insert(*from, vec![Transition::Lambda]);
+            }
+            for (to, mut symbols) in rows {
+                // Lambda sorts after every position in the alphabet
+                symbols.sort_by_key(|t| (order(t).is_none(), order(t)));
+                let symbols: Vec<String> = symbols.iter().map(symbol).collect();
+                out += &format!(
+                    "{} {} {} {}\n",
+                    marker,
+                    name(from),
+                    name(&to),
+                    symbols.join(" ")
+                );
+            }
+        }
+        out
+    }
+
+    /// Parses like [`NFA::from_str`] but skips rows that fail to parse, returning their errors
+    /// next to the NFA of the remaining rows. Only a broken header is fatal.
+    pub fn parse_lenient(s: &str) -> Result<(NFA, Vec<ParseError>), ParseError> {
+        parse(s, true)
+    }
+}
+
+fn parse(s: &str, lenient: bool) -> Result<(NFA, Vec<ParseError>), ParseError> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+        .peekable();
+    let Some(first) = lines.peek().copied() else {
+        return Err(ParseError {
+            kind: ParseErrorKind::EmptyFile,
+            line: 1,
+            column: 1,
+            text: String::new(),
+        });
+    };
+
+    let mut directives: BTreeMap<&str, (&str, Line)> = BTreeMap::new();
+    while let Some((key, value, line)) = lines
+        .peek()
+        .and_then(|l| directive(l.text).map(|(k, v)| (k, v, *l)))
+    {
+        directives.insert(key, (value, line));
+        lines.next();
+    }
+
+    let positional = lines
+        .next_if(|l| {
+            l.text
+                .split_whitespace()
+                .next()
+                .is_some_and(|n| n.parse::<usize>().is_ok())
+        })
+        .map(|line| {
+            let fields = line.fields();
+            match fields.as_slice() {
+                [_, (_, lambda), (column, _), ..] => {
+                    let alphabet_at = line.text.char_indices().nth(column - 1).unwrap().0;
+                    Ok(((*lambda, line), (&line.text[alphabet_at..], line)))
+                }
+                _ => Err(line.error(ParseErrorKind::InvalidFirstLine, line.end())),
+            }
+        })
+        .transpose()?;
+    let header_line = lines.peek().copied().unwrap_or(first);
+    let ((lambda_char, _), (alphabet, alphabet_line)) = match (
+        directives
+            .get("lambda")
+            .copied()
+            .or(positional.map(|p| p.0)),
+        directives
+            .get("alphabet")
+            .copied()
+            .or(positional.map(|p| p.1)),
+    ) {
+        (Some(lambda), Some(alphabet)) => (lambda, alphabet),
+        _ => return Err(header_line.error(ParseErrorKind::MissingHeader, 1)),
+    };
+
+    // Ordering should be preserved and used as the order in the output DFA
+    let mut tokens = Tokens::new();
+    let alphabet: Vec<char> = alphabet
+        .split_whitespace()
+        .map(|s| match named_symbol(s) {
+            Some(c) => Ok(c),
+            None => tokens::intern(
+                &mut tokens,
+                &decode(s.to_string())
+                    .map_err(|_| alphabet_line.error(ParseErrorKind::InvalidEncoding, 1))?,
+            )
+            .ok_or(alphabet_line.error(ParseErrorKind::EmptyAlphabetChar, 1)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut rows: Vec<DefinitionRow> = Vec::new();
+    let mut errors: Vec<ParseError> = Vec::new();
+    for line in lines.filter(|l| !l.text.trim().is_empty()) {
+        match parse_row(line, lambda_char, &mut tokens) {
+            Ok(row) => rows.push(row),
+            Err(e) if lenient => errors.push(e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Numeric files keep their ids, any other name makes every state a named one,
+    // numbered in order of appearance so the first row's state is the start
+    let mut names: Vec<String> = Vec::new();
+    for (_, from, to, _) in &rows {
+        for name in [from, to] {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    // Without a directive the start is the lowest numbered state with a row of its own, which
+    // is the first row's when states are named. A state that is only a target never is.
+    let unpinned = assign_ids(&names, None);
+    let start = directives
+        .get("start")
+        .map(|(s, _)| s.to_string())
+        .or_else(|| {
+            rows.iter()
+                .map(|(_, from, _, _)| from)
+                .min_by_key(|from| unpinned[from])
+                .cloned()
+        });
+    if let Some((value, line)) = directives.get("start")
+        && !names.iter().any(|n| n == value)
+    {
+        let column = line
+            .text
+            .find(value)
+            .map_or(1, |i| line.text[..i].chars().count() + 1);
+        return Err(line.error(ParseErrorKind::UnknownStart, column));
+    }
+    let ids = assign_ids(&names, start.as_ref());
+    let labels = labels(&ids);
+
+    let mut states: BTreeMap<State, (bool, Transitions)> = BTreeMap::new();
+    rows.into_iter().for_each(|(accepting, from, to, chars)| {
+        let (from, to) = (ids[&from], ids[&to]);
+        states
+            .entry(from)
+            .or_insert((accepting, Transitions::default()))
+            .0 |= accepting;
+        chars.iter().for_each(|c| {
+            states
+                .entry(from)
+                .or_insert((accepting, Transitions::default()))
+                .1
+                .entry(*c)
+                .or_default()
+                .insert(to);
+        });
+        // A state that is only ever a target still exists, it just has no way out
+        states.entry(to).or_default();
+    });
+
+    // A start named by the directive may only ever be a target, it still has to be a state
+    if let Some(start) = &start {
+        states.entry(ids[start]).or_default();
+    }
+
+    Ok((
+        NFA {
+            states,
+            alphabet,
+            labels,
+            tokens,
+        },
+        errors,
+    ))
+}
+
+impl FromStr for NFA {
+    type Err = ParseError;
+
+    /// Parses a definition file. The first line is `<state count> <lambda> <alphabet...>`, and
+    /// may be preceded or replaced by `start: <state>`, `lambda: <symbol>` and
+    /// `alphabet: <symbols...>` directive lines, which take precedence over it. Space, tab,
+    /// newline and carriage return are written `SP`, `TAB`, `NL` and `CR`. A symbol of more than
+    /// one character, like `:=`, is a single token, see [`NFA::tokens`].
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        parse(s, false).map(|(nfa, _)| nfa)
+    }
+}
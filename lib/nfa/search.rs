@@ -0,0 +1,13 @@
+use std::ops::Range;
+
+use super::{LazyDfa, NFA};
+
+impl NFA {
+    /// Leftmost-longest matches anywhere in `haystack`, as character ranges. Empty matches are
+    /// skipped. Without `overlapping` the search resumes after each match, with it every
+    /// position gets its own longest match. DFA states are built as the search needs them,
+    /// see [`LazyDfa`].
+    pub fn find_matches(&self, haystack: &[char], overlapping: bool) -> Vec<Range<usize>> {
+        LazyDfa::new(self).find_matches(haystack, overlapping)
+    }
+}
@@ -0,0 +1,83 @@
+//! Random NFA generation for fuzzing code that consumes NFAs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{NFA, Transition, Transitions};
+
+#[derive(Debug, Clone, Copy)]
+pub struct RandomNfaConfig {
+    pub states: usize,
+    /// Symbols are taken from `'a'` onwards
+    pub alphabet_size: usize,
+    /// Probability that a state has a lambda transition to a given other state
+    pub lambda_density: f64,
+    /// Probability that a state has a transition on a given symbol to a given other state
+    pub transition_density: f64,
+    pub accepting_ratio: f64,
+}
+
+impl Default for RandomNfaConfig {
+    fn default() -> Self {
+        Self {
+            states: 8,
+            alphabet_size: 3,
+            lambda_density: 0.05,
+            transition_density: 0.15,
+            accepting_ratio: 0.25,
+        }
+    }
+}
+
+/// xorshift64*, good enough for test input and keeps the crate dependency free
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+pub fn random_nfa(config: &RandomNfaConfig, rng: &mut Rng) -> NFA {
+    let alphabet: Vec<char> = ('a'..).take(config.alphabet_size).collect();
+    let symbols: Vec<Transition> = alphabet
+        .iter()
+        .map(|c| Transition::Char(*c))
+        .chain(std::iter::once(Transition::Lambda))
+        .collect();
+
+    let states = (0..config.states)
+        .map(|from| {
+            let mut transitions = Transitions::new();
+            for symbol in &symbols {
+                let density = match symbol {
+                    Transition::Lambda => config.lambda_density,
+                    Transition::Char(_) => config.transition_density,
+                };
+                let targets: BTreeSet<usize> =
+                    (0..config.states).filter(|_| rng.chance(density)).collect();
+                if !targets.is_empty() {
+                    transitions.insert(*symbol, targets);
+                }
+            }
+            (from, (rng.chance(config.accepting_ratio), transitions))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    NFA::from_parts(states, alphabet)
+}
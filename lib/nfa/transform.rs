@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use transition_tables::{TransitionTable, TransitionTableRow};
+
+use super::{Budget, LimitExceeded, Limits, NFA, Progress, Stage, State, Transition, Transitions};
+use crate::{FiniteAutomaton, LabeledTable, nerode, validate::debug_validate};
+
+/// How [`NFA::minimize`] finds the minimal DFA
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Minimizer {
+    /// Determinizing the reverse of the reverse's determinization, see
+    /// [`NFA::minimize_brzozowski`]
+    #[default]
+    Brzozowski,
+    /// Determinizing, then merging the states of every Myhill–Nerode class found by refining
+    /// the partition into accepting and rejecting states, see [`nerode::refinement`]
+    PartitionRefinement,
+}
+
+impl NFA {
+    /// NFA for the reversed language. States are shifted up by one to make room for a new start
+    /// state 0, which has lambda transitions to every originally accepting state.
+    pub fn reverse(&self) -> NFA {
+        self.reversed().0
+    }
+
+    /// [`NFA::reverse`] and the states a run of it can start from without state 0: the
+    /// originally accepting ones, or state 0 itself when there are none. State 0 has no
+    /// transitions on symbols and none lead to it, so it does not change what a set does, but a
+    /// start set containing it would be told apart from an otherwise equal set reached later.
+    /// Brzozowski's minimization depends on starting from this set.
+    fn reversed(&self) -> (NFA, BTreeSet<State>) {
+        let mut states: BTreeMap<State, (bool, Transitions)> = std::iter::once(0)
+            .chain(self.states.keys().map(|s| s + 1))
+            .map(|s| (s, (false, Transitions::new())))
+            .collect();
+
+        let accepting: BTreeSet<State> = self
+            .states
+            .iter()
+            .filter(|(_, (accepting, _))| *accepting)
+            .map(|(s, _)| s + 1)
+            .collect();
+        if !accepting.is_empty() {
+            states
+                .entry(0)
+                .or_default()
+                .1
+                .insert(Transition::Lambda, accepting.clone());
+        }
+        if let Some(start) = self.start() {
+            states.entry(start + 1).or_default().0 = true;
+        }
+        for (from, (_, transitions)) in &self.states {
+            for (t, targets) in transitions {
+                for to in targets {
+                    states
+                        .entry(to + 1)
+                        .or_default()
+                        .1
+                        .entry(*t)
+                        .or_default()
+                        .insert(from + 1);
+                }
+            }
+        }
+
+        let nfa = Self {
+            states,
+            alphabet: self.alphabet.clone(),
+            labels: self
+                .labels
+                .iter()
+                .map(|(s, l)| (s + 1, l.clone()))
+                .collect(),
+            tokens: self.tokens.clone(),
+        };
+        debug_validate(&nfa, "reverse");
+        let start = match accepting.is_empty() {
+            true => BTreeSet::from([0]),
+            false => accepting,
+        };
+        (nfa, start)
+    }
+
+    /// Subset construction over the reachable sets. States are numbered in discovery order from
+    /// the start set, and the empty set is left out so missing transitions stay missing.
+    pub fn determinize(&self) -> LabeledTable {
+        let start = self.start().into_iter().collect();
+        self.determinize_within(&Budget::unlimited(), Stage::Determinize, &start)
+            .expect("nothing is limited")
+    }
+
+    /// [`NFA::determinize`] giving up once `limits` are passed
+    pub fn try_determinize(&self, limits: &Limits) -> Result<LabeledTable, LimitExceeded> {
+        self.try_determinize_with(limits, &())
+    }
+
+    /// [`NFA::try_determinize`] reporting to `progress` as it goes
+    pub fn try_determinize_with(
+        &self,
+        limits: &Limits,
+        progress: &dyn Progress,
+    ) -> Result<LabeledTable, LimitExceeded> {
+        let start = self.start().into_iter().collect();
+        self.determinize_within(&Budget::new(limits, progress), Stage::Determinize, &start)
+    }
+
+    /// Subset construction from the lambda closure of `start`, see [`NFA::subsets_within`]
+    fn determinize_within(
+        &self,
+        budget: &Budget,
+        stage: Stage,
+        start: &BTreeSet<State>,
+    ) -> Result<LabeledTable, LimitExceeded> {
+        let subsets = self.subsets_within(budget, stage, start)?;
+        let dead = subsets.sets.iter().position(BTreeSet::is_empty);
+        let id = |set: usize| match dead {
+            Some(dead) if set > dead => set - 1,
+            _ => set,
+        };
+
+        let rows = (0..subsets.sets.len())
+            .filter(|set| Some(*set) != dead)
+            .map(|set| TransitionTableRow {
+                id: id(set),
+                accepting: subsets.accepting[set],
+                transitions: subsets.transitions[set]
+                    .iter()
+                    .map(|target| (Some(*target) != dead).then(|| id(*target)))
+                    .collect(),
+            })
+            .collect();
+
+        let mut table = LabeledTable::new(self.alphabet.clone(), TransitionTable { rows })
+            .expect("rows have one column per alphabet symbol");
+        table.tokens = self.tokens.clone();
+        // Named states name their subsets, `{q0,q1}`
+        if !self.labels.is_empty() {
+            table.state_labels = (0..subsets.sets.len())
+                .filter(|set| Some(*set) != dead)
+                .map(|set| {
+                    let names: Vec<String> = subsets.sets[set]
+                        .iter()
+                        .map(|s| self.labels.get(s).cloned().unwrap_or_else(|| s.to_string()))
+                        .collect();
+                    (id(set), format!("{{{}}}", names.join(",")))
+                })
+                .collect();
+        }
+        debug_validate(&table, "determinize");
+        Ok(table)
+    }
+
+    /// Brzozowski's minimization: determinizing the reverse of the reverse's determinization
+    /// yields the minimal DFA, without any partition refinement. The result has no state names,
+    /// its states do not correspond to states of `self`
+    pub fn minimize_brzozowski(&self) -> LabeledTable {
+        self.minimize(Minimizer::Brzozowski)
+    }
+
+    /// The minimal DFA, found the way `minimizer` says. Both ways give the same table up to the
+    /// numbering of states, which cross-checks them.
+    pub fn minimize(&self, minimizer: Minimizer) -> LabeledTable {
+        self.minimize_within(minimizer, &Budget::unlimited())
+            .expect("nothing is limited")
+    }
+
+    /// [`NFA::minimize`] giving up once `limits` are passed, reporting to `progress` as it goes
+    pub fn try_minimize_with(
+        &self,
+        minimizer: Minimizer,
+        limits: &Limits,
+        progress: &dyn Progress,
+    ) -> Result<LabeledTable, LimitExceeded> {
+        self.minimize_within(minimizer, &Budget::new(limits, progress))
+    }
+
+    /// [`NFA::minimize_brzozowski`] giving up once `limits` are passed, counted separately for
+    /// each of the two determinizations except for the timeout
+    pub fn try_minimize_brzozowski(&self, limits: &Limits) -> Result<LabeledTable, LimitExceeded> {
+        self.try_minimize_brzozowski_with(limits, &())
+    }
+
+    /// [`NFA::try_minimize_brzozowski`] reporting to `progress` as it goes
+    pub fn try_minimize_brzozowski_with(
+        &self,
+        limits: &Limits,
+        progress: &dyn Progress,
+    ) -> Result<LabeledTable, LimitExceeded> {
+        self.try_minimize_with(Minimizer::Brzozowski, limits, progress)
+    }
+
+    fn minimize_within(
+        &self,
+        minimizer: Minimizer,
+        budget: &Budget,
+    ) -> Result<LabeledTable, LimitExceeded> {
+        match minimizer {
+            Minimizer::Brzozowski => {
+                let (reversed, start) = self.reversed();
+                let table = reversed.determinize_within(budget, Stage::MinimizeReversed, &start)?;
+                let (reversed, start) = NFA::from(&table).reversed();
+                let mut table = reversed.determinize_within(budget, Stage::Minimize, &start)?;
+                table.state_labels.clear();
+                Ok(table)
+            }
+            Minimizer::PartitionRefinement => {
+                let start = self.start().into_iter().collect();
+                let table = self.determinize_within(budget, Stage::Determinize, &start)?;
+                Ok(nerode::quotient(&table))
+            }
+        }
+    }
+
+    /// Equivalent NFA without lambda transitions that is usually much smaller, making a cheaper
+    /// input to [`NFA::determinize`]. States that are unreachable or cannot reach acceptance are
+    /// dropped, then bisimilar states, those with the same acceptance that move on every symbol
+    /// into the same groups, are merged into the lowest numbered of them.
+    pub fn optimize(&self) -> NFA {
+        let nfa = self.remove_lambdas();
+        let Some(start) = nfa.start() else {
+            return nfa;
+        };
+        let useful: BTreeSet<State> = nfa
+            .reachable_states()
+            .intersection(&nfa.live_states())
+            .copied()
+            .collect();
+        if !useful.contains(&start) {
+            let mut empty = Self::from_parts(
+                BTreeMap::from([(start, (false, Transitions::new()))]),
+                nfa.alphabet.clone(),
+            );
+            empty.labels = nfa
+                .labels
+                .into_iter()
+                .filter(|(s, _)| *s == start)
+                .collect();
+            empty.tokens = nfa.tokens;
+            return empty;
+        }
+
+        // Partition refinement: groups split by where their states move until none does
+        let mut group: BTreeMap<State, usize> = useful
+            .iter()
+            .map(|s| (*s, usize::from(nfa.is_accepting(*s))))
+            .collect();
+        let mut groups = group.values().collect::<BTreeSet<_>>().len();
+        loop {
+            let signatures: BTreeMap<State, (usize, BTreeSet<(Transition, usize)>)> = useful
+                .iter()
+                .map(|s| {
+                    let moves = nfa.states[s]
+                        .1
+                        .iter()
+                        .flat_map(|(t, targets)| {
+                            targets
+                                .iter()
+                                .filter_map(|target| group.get(target))
+                                .map(|g| (*t, *g))
+                        })
+                        .collect();
+                    (*s, (group[s], moves))
+                })
+                .collect();
+            let mut ids = BTreeMap::new();
+            for (s, signature) in &signatures {
+                let next = ids.len();
+                group.insert(*s, *ids.entry(signature).or_insert(next));
+            }
+            if ids.len() == groups {
+                break;
+            }
+            groups = ids.len();
+        }
+
+        // The start state is the lowest of all, so it stays the lowest
+        let mut representative: BTreeMap<usize, State> = BTreeMap::new();
+        for (s, g) in &group {
+            representative.entry(*g).or_insert(*s);
+        }
+        let states = representative
+            .values()
+            .map(|s| {
+                let (accepting, transitions) = &nfa.states[s];
+                let transitions = transitions
+                    .iter()
+                    .map(|(t, targets)| {
+                        let targets: BTreeSet<State> = targets
+                            .iter()
+                            .filter_map(|target| group.get(target))
+                            .map(|g| representative[g])
+                            .collect();
+                        (*t, targets)
+                    })
+                    .filter(|(_, targets)| !targets.is_empty())
+                    .collect();
+                (*s, (*accepting, transitions))
+            })
+            .collect();
+
+        let optimized = Self {
+            states,
+            alphabet: nfa.alphabet,
+            labels: nfa
+                .labels
+                .into_iter()
+                .filter(|(s, _)| representative.values().any(|r| r == s))
+                .collect(),
+            tokens: nfa.tokens,
+        };
+        debug_validate(&optimized, "optimize");
+        optimized
+    }
+}
+
+impl LabeledTable {
+    pub fn reverse(&self) -> NFA {
+        NFA::from(self).reverse()
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{Budget, LimitExceeded, Limits, NFA, Progress, Stage, State, Transition};
+use crate::{Digraph, LabelStyle, TableFormat, digraph::escape};
+
+/// A DFA state found by the subset construction
+pub struct WorksheetRow {
+    /// The set the state was first reached as, before taking its lambda closure
+    pub set: BTreeSet<State>,
+    /// The NFA states making up the DFA state
+    pub closure: BTreeSet<State>,
+    pub accepting: bool,
+    /// Index of the row reached on each symbol in alphabet order, `None` for the empty set
+    pub targets: Vec<Option<usize>>,
+}
+
+/// The subset construction as worked by hand: rows in the order the DFA states are found,
+/// numbered like the states of [`NFA::determinize`]
+pub struct Worksheet {
+    pub alphabet: Vec<char>,
+    /// The start state first
+    pub rows: Vec<WorksheetRow>,
+    labels: BTreeMap<State, String>,
+}
+
+impl NFA {
+    pub fn worksheet(&self) -> Worksheet {
+        self.worksheet_within(&Budget::unlimited())
+            .expect("nothing is limited")
+    }
+
+    /// [`NFA::worksheet`] giving up once `limits` are passed, reporting to `progress` as it goes
+    pub fn try_worksheet_with(
+        &self,
+        limits: &Limits,
+        progress: &dyn Progress,
+    ) -> Result<Worksheet, LimitExceeded> {
+        self.worksheet_within(&Budget::new(limits, progress))
+    }
+
+    fn worksheet_within(&self, budget: &Budget) -> Result<Worksheet, LimitExceeded> {
+        let start: BTreeSet<State> = self.start().into_iter().collect();
+        let mut rows = vec![WorksheetRow {
+            closure: self.lambda_closure(&start),
+            set: start,
+            accepting: false,
+            targets: Vec::new(),
+        }];
+        // Bytes of the sets and rows so far, counted like the subset construction does
+        let mut memory = 0;
+        let mut i = 0;
+        while i < rows.len() {
+            let closure = rows[i].closure.clone();
+            let mut targets = Vec::new();
+            for c in &self.alphabet {
+                let set: BTreeSet<State> = closure
+                    .iter()
+                    .flat_map(|s| self.targets(*s, Transition::Char(*c)))
+                    .collect();
+                let next = self.lambda_closure(&set);
+                if next.is_empty() {
+                    targets.push(None);
+                    continue;
+                }
+                let index = match rows.iter().position(|r| r.closure == next) {
+                    Some(index) => index,
+                    None => {
+                        memory += (set.len() + next.len()) * size_of::<State>();
+                        rows.push(WorksheetRow {
+                            set,
+                            closure: next,
+                            accepting: false,
+                            targets: Vec::new(),
+                        });
+                        rows.len() - 1
+                    }
+                };
+                targets.push(Some(index));
+            }
+            rows[i].accepting = closure.iter().any(|s| self.is_accepting(*s));
+            rows[i].targets = targets;
+            memory += self.alphabet.len() * size_of::<Option<usize>>();
+            budget.report(Stage::Determinize, i + 1, rows.len());
+            budget.check(rows.len(), memory)?;
+            i += 1;
+        }
+
+        Ok(Worksheet {
+            alphabet: self.alphabet.clone(),
+            rows,
+            labels: self.labels.clone(),
+        })
+    }
+
+    /// The subset construction drawn over the NFA: every DFA state of [`NFA::worksheet`] is a box
+    /// holding a copy of the NFA states making it up with the NFA transitions between them, and
+    /// the DFA transitions go from box to box
+    pub fn subset_overlay(&self) -> Digraph {
+        self.overlay(&self.worksheet())
+    }
+
+    /// [`NFA::subset_overlay`] giving up once `limits` are passed, reporting to `progress` as it
+    /// goes
+    pub fn try_subset_overlay_with(
+        &self,
+        limits: &Limits,
+        progress: &dyn Progress,
+    ) -> Result<Digraph, LimitExceeded> {
+        Ok(self.overlay(&self.try_worksheet_with(limits, progress)?))
+    }
+
+    fn overlay(&self, worksheet: &Worksheet) -> Digraph {
+        let mut graph = Digraph::default();
+        let mut next = 0;
+        for (i, row) in worksheet.rows.iter().enumerate() {
+            let mut copies: BTreeMap<State, usize> = BTreeMap::new();
+            for state in &row.closure {
+                copies.insert(*state, next);
+                let label = self
+                    .labels
+                    .get(state)
+                    .cloned()
+                    .unwrap_or_else(|| state.to_string());
+                graph.labels.insert(next, escape(&label));
+                if self.is_accepting(*state) {
+                    graph.accepting_nodes.insert(next);
+                }
+                next += 1;
+            }
+            let mut nodes: BTreeSet<usize> = copies.values().copied().collect();
+            if nodes.is_empty() {
+                graph.labels.insert(next, "&empty;".to_owned());
+                nodes.insert(next);
+                next += 1;
+            }
+
+            for (state, copy) in &copies {
+                let Some((_, transitions)) = self.states.get(state) else {
+                    continue;
+                };
+                for (transition, targets) in transitions {
+                    let targets: BTreeSet<usize> = targets
+                        .iter()
+                        .filter_map(|t| copies.get(t))
+                        .copied()
+                        .collect();
+                    if targets.is_empty() {
+                        continue;
+                    }
+                    graph
+                        .edge_colors
+                        .insert((*copy, targets.clone()), "gray".to_owned());
+                    graph
+                        .edges
+                        .entry((*copy, targets))
+                        .and_modify(|acc| {
+                            acc.push('|');
+                            acc.push_str(&transition.to_string());
+                        })
+                        .or_insert(transition.to_string());
+                }
+            }
+            graph.clusters.push((i.to_string(), nodes));
+
+            for (c, target) in self.alphabet.iter().zip(&row.targets) {
+                let Some(target) = target else {
+                    continue;
+                };
+                graph
+                    .cluster_edges
+                    .entry((i, *target))
+                    .and_modify(|acc| {
+                        acc.push('|');
+                        acc.push_str(&Transition::Char(*c).to_string());
+                    })
+                    .or_insert(Transition::Char(*c).to_string());
+            }
+        }
+        graph.start = graph
+            .clusters
+            .first()
+            .and_then(|(_, nodes)| nodes.first().copied());
+
+        graph
+    }
+}
+
+impl Worksheet {
+    fn set(&self, set: &BTreeSet<State>) -> String {
+        if set.is_empty() {
+            return "∅".to_owned();
+        }
+        let names: Vec<String> = set
+            .iter()
+            .map(|s| self.labels.get(s).cloned().unwrap_or_else(|| s.to_string()))
+            .collect();
+        format!("{{{}}}", names.join(","))
+    }
+
+    /// One line per DFA state: whether it accepts, its number, the set it was reached as, its
+    /// lambda closure and per symbol the closure reached and its number
+    pub fn render(&self, format: TableFormat) -> String {
+        let header = ["", "DFA state", "set", "λ-closure"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain(
+                self.alphabet
+                    .iter()
+                    .map(|c| Transition::Char(*c).render(&LabelStyle::text())),
+            )
+            .collect();
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                [
+                    if row.accepting { "+" } else { "-" }.to_owned(),
+                    i.to_string(),
+                    self.set(&row.set),
+                    self.set(&row.closure),
+                ]
+                .into_iter()
+                .chain(row.targets.iter().map(|t| match t {
+                    Some(t) => format!("{} ({})", self.set(&self.rows[*t].closure), t),
+                    None => "∅".to_owned(),
+                }))
+                .collect()
+            })
+            .collect();
+        format.render(header, rows)
+    }
+}
@@ -0,0 +1,288 @@
+//! Pushdown automata: definition format, simulation and rendering
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{
+    Digraph, Transition,
+    digraph::escaped,
+    import::{DefinitionError, MachineDefinition, labels},
+    nfa::{State, named_symbol},
+};
+
+#[derive(Debug)]
+pub enum PdaError {
+    /// Line number of a row without `<marker> <from> <to> <input> <pop> <push>`
+    ColumnMismatch(usize),
+    InvalidSymbol(String),
+    NoTransitions,
+    UnknownStart(String),
+}
+impl Error for PdaError {}
+impl From<DefinitionError> for PdaError {
+    fn from(value: DefinitionError) -> Self {
+        match value {
+            DefinitionError::ColumnMismatch(line) => PdaError::ColumnMismatch(line),
+            DefinitionError::NoTransitions => PdaError::NoTransitions,
+            DefinitionError::UnknownStart(start) => PdaError::UnknownStart(start),
+        }
+    }
+}
+impl Display for PdaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PdaError::ColumnMismatch(line) => write!(
+                f,
+                "ColumnMismatch: Line {} is not <marker> <from> <to> <input> <pop> <push>!",
+                line
+            ),
+            PdaError::InvalidSymbol(s) => {
+                write!(f, "InvalidSymbol: {:?} is not a single symbol!", s)
+            }
+            PdaError::NoTransitions => write!(f, "NoTransitions: Definition has no rows!"),
+            PdaError::UnknownStart(s) => {
+                write!(
+                    f,
+                    "UnknownStart: The start: directive names no state {:?}!",
+                    s
+                )
+            }
+        }
+    }
+}
+
+/// Reads `input`, pops `pop` and pushes `push`, `None` being lambda for the first two
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdaTransition {
+    pub from: State,
+    pub to: State,
+    pub input: Option<char>,
+    pub pop: Option<char>,
+    /// Top of the stack first, like it is written
+    pub push: Vec<char>,
+}
+
+impl Display for PdaTransition {
+    /// `a, X/YZ`, with lambda for a missing input, pop or push
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = |c: Option<char>| c.map_or(Transition::Lambda, Transition::Char).to_string();
+        let push: String = match self.push.is_empty() {
+            true => Transition::Lambda.to_string(),
+            false => self.push.iter().map(|c| symbol(Some(*c))).collect(),
+        };
+        write!(f, "{}, {}/{}", symbol(self.input), symbol(self.pop), push)
+    }
+}
+
+/// A pushdown automaton accepting by final state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PDA {
+    states: BTreeMap<State, bool>,
+    transitions: Vec<PdaTransition>,
+    initial_stack: char,
+    labels: BTreeMap<State, String>,
+}
+
+/// A state, how much of the input has been read and the stack, top last
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Configuration {
+    pub state: State,
+    pub position: usize,
+    pub stack: Vec<char>,
+}
+
+impl PDA {
+    /// Accepting flag of every state
+    pub fn states(&self) -> &BTreeMap<State, bool> {
+        &self.states
+    }
+
+    pub fn transitions(&self) -> &[PdaTransition] {
+        &self.transitions
+    }
+
+    /// Original names of states defined by name, numeric states have none
+    pub fn labels(&self) -> &BTreeMap<State, String> {
+        &self.labels
+    }
+
+    /// The name a state was defined with, or its id
+    pub fn state_name(&self, id: State) -> String {
+        self.labels
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// The lowest numbered state
+    pub fn start(&self) -> Option<State> {
+        self.states.keys().next().copied()
+    }
+
+    /// No configuration ever has two moves: two transitions out of one state conflict unless
+    /// they read different input symbols or pop different stack symbols, lambda matching both
+    pub fn is_deterministic(&self) -> bool {
+        let overlaps = |a: Option<char>, b: Option<char>| a.is_none() || b.is_none() || a == b;
+        self.transitions.iter().enumerate().all(|(i, t)| {
+            self.transitions[i + 1..]
+                .iter()
+                .all(|u| u.from != t.from || !overlaps(t.input, u.input) || !overlaps(t.pop, u.pop))
+        })
+    }
+
+    fn moves<'a>(
+        &'a self,
+        config: &'a Configuration,
+        input: &'a [char],
+    ) -> impl Iterator<Item = Configuration> + 'a {
+        self.transitions
+            .iter()
+            .filter(move |t| t.from == config.state)
+            .filter(move |t| {
+                t.input
+                    .is_none_or(|c| input.get(config.position) == Some(&c))
+            })
+            .filter(move |t| t.pop.is_none_or(|c| config.stack.last() == Some(&c)))
+            .map(move |t| {
+                let mut stack = config.stack.clone();
+                if t.pop.is_some() {
+                    stack.pop();
+                }
+                stack.extend(t.push.iter().rev());
+                Configuration {
+                    state: t.to,
+                    position: config.position + usize::from(t.input.is_some()),
+                    stack,
+                }
+            })
+    }
+
+    /// Every configuration reachable on `input`, one set per step starting with the initial
+    /// configuration. Stops when no moves are left, when a step changes nothing, or after
+    /// `max_steps`, since lambda moves can go on forever.
+    pub fn trace(&self, input: &str, max_steps: usize) -> Vec<BTreeSet<Configuration>> {
+        let input: Vec<char> = input.chars().collect();
+        let initial: BTreeSet<Configuration> = self
+            .start()
+            .map(|state| Configuration {
+                state,
+                position: 0,
+                stack: vec![self.initial_stack],
+            })
+            .into_iter()
+            .collect();
+        let mut steps = vec![initial];
+        while steps.len() <= max_steps {
+            let next: BTreeSet<Configuration> = steps
+                .last()
+                .expect("starts with the initial step")
+                .iter()
+                .flat_map(|config| self.moves(config, &input))
+                .collect();
+            if next.is_empty() || steps.last() == Some(&next) {
+                break;
+            }
+            steps.push(next);
+        }
+        steps
+    }
+
+    /// Whether an accepting state is reached with all of `input` read within `max_steps` moves
+    pub fn accepts(&self, input: &str, max_steps: usize) -> bool {
+        self.trace_accepts(&self.trace(input, max_steps), input)
+    }
+
+    /// Whether `trace`, a [`PDA::trace`] of `input`, reaches an accepting state with all of the
+    /// input consumed
+    pub fn trace_accepts(&self, trace: &[BTreeSet<Configuration>], input: &str) -> bool {
+        let length = input.chars().count();
+        trace
+            .iter()
+            .flatten()
+            .any(|c| c.position == length && self.states.get(&c.state) == Some(&true))
+    }
+}
+
+fn symbol(token: &str, lambda: &str) -> Result<Option<char>, PdaError> {
+    if token == lambda {
+        return Ok(None);
+    }
+    let mut chars = token.chars();
+    match (named_symbol(token), chars.next(), chars.next()) {
+        (Some(c), _, _) | (None, Some(c), None) => Ok(Some(c)),
+        _ => Err(PdaError::InvalidSymbol(token.to_owned())),
+    }
+}
+
+impl FromStr for PDA {
+    type Err = PdaError;
+
+    /// Rows are `<+|-> <from> <to> <input> <pop> <push>`, a `+` marking `from` as accepting and
+    /// `push` written top first. Leading `start: <state>`, `lambda: <symbol>` (default `&`) and
+    /// `initial: <stack symbol>` (default `Z`) directives may come first. The start state is
+    /// the first row's `from` unless given. An accepting state without moves of its own can be
+    /// written as a `+ q q & & &` row, which changes nothing.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let definition = MachineDefinition::parse(s, &["start", "lambda", "initial"])?;
+        let lambda = definition.directives.get("lambda").copied().unwrap_or("&");
+        let initial = definition.directives.get("initial").copied().unwrap_or("Z");
+        let initial_stack =
+            symbol(initial, lambda)?.ok_or_else(|| PdaError::InvalidSymbol(initial.to_owned()))?;
+        let ids = definition.ids();
+
+        let mut states: BTreeMap<State, bool> = ids.values().map(|id| (*id, false)).collect();
+        let mut transitions = Vec::new();
+        for row in &definition.rows {
+            let [input, pop, push] = row.fields;
+            let from = ids[&row.from.to_string()];
+            *states.entry(from).or_default() |= row.accepting;
+            transitions.push(PdaTransition {
+                from,
+                to: ids[&row.to.to_string()],
+                input: symbol(input, lambda)?,
+                pop: symbol(pop, lambda)?,
+                push: match push == lambda {
+                    true => Vec::new(),
+                    false => push.chars().collect(),
+                },
+            });
+        }
+
+        Ok(PDA {
+            states,
+            transitions,
+            initial_stack,
+            labels: labels(&ids),
+        })
+    }
+}
+
+/// Edges are labeled `a, X/YZ`, several transitions between the same states one per line
+impl From<&PDA> for Digraph {
+    fn from(value: &PDA) -> Self {
+        let mut graph = Digraph {
+            start: value.start(),
+            labels: escaped(&value.labels),
+            accepting_nodes: value
+                .states
+                .iter()
+                .filter(|(_, accepting)| **accepting)
+                .map(|(s, _)| *s)
+                .collect(),
+            ..Default::default()
+        };
+        for t in &value.transitions {
+            graph
+                .edges
+                .entry((t.from, BTreeSet::from([t.to])))
+                .and_modify(|acc| {
+                    acc.push_str("<br/>");
+                    acc.push_str(&t.to_string());
+                })
+                .or_insert(t.to_string());
+        }
+        graph
+    }
+}
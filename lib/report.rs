@@ -0,0 +1,276 @@
+//! Self-contained HTML summary of an automaton
+
+use crate::{
+    Automaton, Digraph, LabelStyle, LabeledTable, LimitExceeded, Limits, Progress, Stats,
+    digraph::escape,
+    nerode::{Classes, PartitionStep},
+    tm::{Halt, Run, TM},
+};
+
+fn table_html(table: &LabeledTable) -> String {
+    let mut html = String::from("<table>\n<tr><th></th><th>state</th>");
+    for column in 0..table.alphabet().len() {
+        html += &format!(
+            "<th>{}</th>",
+            escape(&table.symbol_name(column, &LabelStyle::text()))
+        );
+    }
+    html += "</tr>\n";
+    for row in &table.table().rows {
+        let state = match table.state_outputs.get(&row.id) {
+            Some(output) => format!("{}/{}", row.id, output),
+            None => row.id.to_string(),
+        };
+        html += &format!(
+            "<tr><td>{}</td><th>{}</th>",
+            if row.accepting { "+" } else { "-" },
+            escape(&state)
+        );
+        for column in 0..table.alphabet().len() {
+            let cell = match row.transitions.get(column).copied().flatten() {
+                Some(target) => match table.transition_outputs.get(&(row.id, column)) {
+                    Some(output) => escape(&format!("{}/{}", target, output)),
+                    None => target.to_string(),
+                },
+                None => "&empty;".to_owned(),
+            };
+            html += &format!("<td>{}</td>", cell);
+        }
+        html += "</tr>\n";
+    }
+    html + "</table>\n"
+}
+
+/// One HTML page with the DFA transition table, the graph as DOT, statistics before and after
+/// minimization and up to `examples` accepted strings. Nothing is loaded from elsewhere, the
+/// DOT source is embedded as text since rendering it needs Graphviz.
+pub fn html(title: &str, automaton: &Automaton, examples: usize) -> String {
+    try_html_with(title, automaton, examples, &Limits::default(), &()).expect("nothing is limited")
+}
+
+/// [`html`] giving up once determinizing or minimizing passes `limits`, reporting to `progress`
+/// as it goes
+pub fn try_html_with(
+    title: &str,
+    automaton: &Automaton,
+    examples: usize,
+    limits: &Limits,
+    progress: &dyn Progress,
+) -> Result<String, LimitExceeded> {
+    let nfa = automaton.to_nfa();
+    let table = match automaton {
+        Automaton::Table(tt) => tt,
+        Automaton::Nfa(nfa) => &nfa.try_determinize_with(limits, progress)?,
+    };
+    let minimal = nfa.try_minimize_brzozowski_with(limits, progress)?;
+    // No length bound is needed, enumeration ends by itself once no prefix can be accepted
+    let words: String = nfa
+        .enumerate_accepted(usize::MAX)
+        .take(examples)
+        .map(|w| format!("<li><code>{}</code></li>\n", escape(&format!("{:?}", w))))
+        .collect();
+
+    Ok(format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #999; padding: 0.2em 0.6em; text-align: center; }}
+pre {{ background: #f4f4f4; padding: 1em; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<h2>Transition table</h2>
+{table}<h2>Graph</h2>
+<pre>{graph}</pre>
+<h2>Statistics</h2>
+<pre>{stats}</pre>
+<h2>Minimal DFA</h2>
+<p>{before} states as a DFA, {after} after minimization.</p>
+<h2>Accepted strings</h2>
+<ul>
+{words}</ul>
+</body>
+</html>
+",
+        title = escape(title),
+        table = table_html(table),
+        graph = escape(&Digraph::from(automaton).to_string()),
+        stats = escape(&Stats::from(automaton).to_string()),
+        before = table.table().rows.len(),
+        after = minimal.table().rows.len(),
+        words = words,
+    ))
+}
+
+/// One HTML page with a Turing machine run, a table row per step with the cell under the head
+/// highlighted
+pub fn tape_html(title: &str, tm: &TM, run: &Run) -> String {
+    let rows: String = run
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let tape: String = step
+                .tape
+                .iter()
+                .enumerate()
+                .map(|(j, c)| match j == step.head {
+                    true => format!("<td class=\"head\">{}</td>", escape(&c.to_string())),
+                    false => format!("<td>{}</td>", escape(&c.to_string())),
+                })
+                .collect();
+            format!(
+                "<tr><th>{}</th><th>{}</th>{}</tr>\n",
+                i,
+                escape(&tm.state_name(step.state)),
+                tape
+            )
+        })
+        .collect();
+    let halt = match run.halt {
+        Halt::Accepted => "accepted",
+        Halt::Rejected => "rejected",
+        Halt::StepLimit => "stopped at the step limit",
+    };
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #999; padding: 0.2em 0.6em; text-align: center; }}
+td {{ font-family: monospace; }}
+td.head {{ background: #fd6; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{count} steps, {halt}.</p>
+<table>
+{rows}</table>
+</body>
+</html>
+",
+        title = escape(title),
+        count = run.steps.len().saturating_sub(1),
+        halt = halt,
+        rows = rows,
+    )
+}
+
+/// One HTML page with the Myhill–Nerode classes of a DFA, their access strings, a table of the
+/// strings distinguishing every two classes, the rounds of refinement finding them and the graph
+/// as DOT with the classes boxed in
+pub fn nerode_html(
+    title: &str,
+    table: &LabeledTable,
+    classes: &Classes,
+    steps: &[PartitionStep],
+) -> String {
+    let rows: String = classes
+        .classes
+        .iter()
+        .enumerate()
+        .map(|(i, class)| {
+            let states: Vec<String> = class.states.iter().map(|s| table.state_name(*s)).collect();
+            let access = class
+                .access
+                .as_ref()
+                .map_or("&empty;".to_owned(), |a| escape(&format!("{:?}", a)));
+            format!(
+                "<tr><td>{}</td><th>{}</th><td>{}</td><td><code>{}</code></td></tr>\n",
+                if class.accepting { "+" } else { "-" },
+                i,
+                escape(&states.join(" ")),
+                access
+            )
+        })
+        .collect();
+    let mut matrix = String::from("<tr><th></th>");
+    for i in 0..classes.classes.len() {
+        matrix += &format!("<th>{}</th>", i);
+    }
+    matrix += "</tr>\n";
+    for i in 0..classes.classes.len() {
+        matrix += &format!("<tr><th>{}</th>", i);
+        for j in 0..classes.classes.len() {
+            let cell = classes
+                .distinguishing
+                .get(&(i.min(j), i.max(j)))
+                .map_or(String::new(), |w| {
+                    format!("<code>{}</code>", escape(&format!("{:?}", w)))
+                });
+            matrix += &format!("<td>{}</td>", cell);
+        }
+        matrix += "</tr>\n";
+    }
+    let rounds: String = steps
+        .iter()
+        .map(|step| {
+            let blocks: Vec<String> = step
+                .blocks
+                .iter()
+                .map(|block| {
+                    let states: Vec<String> = block.iter().map(|s| table.state_name(*s)).collect();
+                    format!("{{{}}}", states.join(" "))
+                })
+                .collect();
+            let splitter = match step.splitter {
+                Some((block, c)) => escape(&format!("block {} on {:?}", block, c)),
+                None => "accepting or not".to_owned(),
+            };
+            format!(
+                "<li>{}: <code>{}</code></li>\n",
+                splitter,
+                escape(&blocks.join(" "))
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #999; padding: 0.2em 0.6em; text-align: center; }}
+pre {{ background: #f4f4f4; padding: 1em; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<h2>Equivalence classes</h2>
+<table>
+<tr><th></th><th>class</th><th>states</th><th>access string</th></tr>
+{rows}</table>
+<h2>Distinguishing strings</h2>
+<table>
+{matrix}</table>
+<h2>Refinement</h2>
+<ol start=\"0\">
+{rounds}</ol>
+<h2>Graph</h2>
+<pre>{graph}</pre>
+</body>
+</html>
+",
+        title = escape(title),
+        rows = rows,
+        matrix = matrix,
+        rounds = rounds,
+        graph = escape(&classes.to_digraph(table).to_string()),
+    )
+}
@@ -0,0 +1,132 @@
+//! Step-by-step simulation of a DFA that can be rewound
+
+use std::collections::BTreeMap;
+
+use crate::LabeledTable;
+
+type State = usize;
+
+/// A DFA consuming its input one symbol at a time, keeping every state it passed through so it
+/// can step back or jump to any position
+pub struct DfaRun<'a> {
+    table: &'a LabeledTable,
+    /// Row index of every state
+    rows: BTreeMap<State, usize>,
+    input: Vec<char>,
+    /// The state after each consumed symbol, the start state first, `None` from the first
+    /// missing transition on
+    path: Vec<Option<State>>,
+}
+
+impl<'a> DfaRun<'a> {
+    /// Starts in the first row's state before the first symbol of `input`
+    pub fn new(table: &'a LabeledTable, input: &str) -> Self {
+        let rows = table
+            .table()
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.id, i))
+            .collect();
+        Self {
+            table,
+            rows,
+            input: input.chars().collect(),
+            path: vec![table.table().rows.first().map(|r| r.id)],
+        }
+    }
+
+    pub fn table(&self) -> &'a LabeledTable {
+        self.table
+    }
+
+    pub fn input(&self) -> &[char] {
+        &self.input
+    }
+
+    /// Number of symbols consumed
+    pub fn position(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    /// The symbols not consumed yet
+    pub fn remaining(&self) -> &[char] {
+        &self.input[self.position()..]
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() == self.input.len()
+    }
+
+    /// The current state, `None` once a missing transition was taken
+    pub fn state(&self) -> Option<State> {
+        *self.path.last().expect("starts with the start state")
+    }
+
+    pub fn is_accepting(&self) -> bool {
+        self.state()
+            .and_then(|s| self.rows.get(&s))
+            .is_some_and(|i| self.table.table().rows[*i].accepting)
+    }
+
+    /// Whether the whole input has been consumed into an accepting state
+    pub fn accepted(&self) -> bool {
+        self.is_finished() && self.is_accepting()
+    }
+
+    /// The state after each consumed symbol, starting with the start state
+    pub fn path(&self) -> &[Option<State>] {
+        &self.path
+    }
+
+    /// Position of the symbol that had no transition, if the run has taken one. A table without
+    /// states has no start state to be stuck after, so its runs are never stuck.
+    pub fn stuck_at(&self) -> Option<usize> {
+        self.path
+            .iter()
+            .position(Option::is_none)
+            .and_then(|i| i.checked_sub(1))
+    }
+
+    /// Consumes the next symbol, returning the state it leads to, or `None` with nothing left
+    pub fn step(&mut self) -> Option<Option<State>> {
+        let c = self.input.get(self.position())?;
+        let column = self.table.alphabet().iter().position(|a| a == c);
+        let next = self.state().and_then(|s| {
+            let row = &self.table.table().rows[*self.rows.get(&s)?];
+            row.transitions.get(column?).copied().flatten()
+        });
+        self.path.push(next);
+        Some(next)
+    }
+
+    /// Undoes the last step, `false` at the start of the input
+    pub fn step_back(&mut self) -> bool {
+        match self.path.len() > 1 {
+            true => {
+                self.path.pop();
+                true
+            }
+            false => false,
+        }
+    }
+
+    /// Moves to just after the first `position` symbols, or to the end of a shorter input
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.input.len());
+        self.path.truncate(position + 1);
+        while self.position() < position {
+            self.step();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.seek(0);
+    }
+
+    /// Consumes the rest of the input and returns the final state
+    pub fn finish(&mut self) -> Option<State> {
+        self.seek(self.input.len());
+        self.state()
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(feature = "rand")]
+use std::collections::{BTreeSet, VecDeque};
+
+use num_bigint::BigUint;
+#[cfg(feature = "rand")]
+use num_bigint::RandBigInt;
+
+use crate::LabeledTable;
+#[cfg(feature = "rand")]
+use crate::run::DfaRun;
+
+impl LabeledTable {
+    /// `counts[k][row]` is the number of strings of exactly `k` characters accepted from the
+    /// state of `row`, for every `k` up to `len`
+    fn accepted_counts(&self, targets: &[Vec<Option<usize>>], len: usize) -> Vec<Vec<BigUint>> {
+        let mut counts: Vec<Vec<BigUint>> = vec![
+            self.table()
+                .rows
+                .iter()
+                .map(|r| BigUint::from(u8::from(r.accepting)))
+                .collect(),
+        ];
+        for k in 1..=len {
+            let shorter = &counts[k - 1];
+            let row = targets
+                .iter()
+                .map(|row| row.iter().flatten().map(|t| &shorter[*t]).sum())
+                .collect();
+            counts.push(row);
+        }
+        counts
+    }
+
+    /// Number of accepted strings of exactly `len` characters, by dynamic programming over the
+    /// table instead of enumeration
+    pub fn count_accepted(&self, len: usize) -> BigUint {
+        self.accepted_counts(&self.target_rows(), len)[len]
+            .first()
+            .cloned()
+            .unwrap_or(BigUint::ZERO)
+    }
+
+    /// An accepted string of exactly `len` characters, every one equally likely. `None` if
+    /// there is none.
+    #[cfg(feature = "rand")]
+    pub fn sample_accepted<R: rand::Rng + ?Sized>(
+        &self,
+        len: usize,
+        rng: &mut R,
+    ) -> Option<String> {
+        let targets = self.target_rows();
+        let counts = self.accepted_counts(&targets, len);
+        self.sample_from(&targets, &counts, len, rng)
+    }
+
+    /// [`LabeledTable::sample_accepted`] with `counts` computed for at least `len` characters
+    #[cfg(feature = "rand")]
+    fn sample_from<R: rand::Rng + ?Sized>(
+        &self,
+        targets: &[Vec<Option<usize>>],
+        counts: &[Vec<BigUint>],
+        len: usize,
+        rng: &mut R,
+    ) -> Option<String> {
+        let mut row = 0;
+        if counts[len].get(row).is_none_or(|c| *c == BigUint::ZERO) {
+            return None;
+        }
+
+        // Each symbol is picked with probability proportional to the strings it leads to
+        let mut word = String::new();
+        for remaining in (1..=len).rev() {
+            let mut pick = rng.gen_biguint_below(&counts[remaining][row]);
+            for (column, target) in targets[row].iter().enumerate() {
+                let Some(next) = *target else {
+                    continue;
+                };
+                let count = &counts[remaining - 1][next];
+                if pick < *count {
+                    word.push(self.alphabet()[column]);
+                    row = next;
+                    break;
+                }
+                pick -= count;
+            }
+        }
+        Some(word)
+    }
+
+    /// `count` random accepted strings of at most `max_len` characters, for a corpus of valid
+    /// input. As long as some transition on an accepted path that short is not taken by an
+    /// earlier string, the next one is the shortest through a random such transition. After
+    /// that, a length with accepted strings is picked uniformly and a string of that length is
+    /// sampled with [`LabeledTable::sample_accepted`]. Empty if no string is short enough.
+    #[cfg(feature = "rand")]
+    pub fn generate_accepted<R: rand::Rng + ?Sized>(
+        &self,
+        count: usize,
+        max_len: usize,
+        rng: &mut R,
+    ) -> Vec<String> {
+        let targets = self.target_rows();
+        let counts = self.accepted_counts(&targets, max_len);
+        let lengths: Vec<usize> = (0..=max_len)
+            .filter(|len| counts[*len].first().is_some_and(|c| *c != BigUint::ZERO))
+            .collect();
+        if lengths.is_empty() {
+            return Vec::new();
+        }
+
+        let paths = ShortestPaths::new(self, &targets);
+        let mut uncovered: Vec<(usize, usize)> = targets
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| t.is_some())
+                    .map(move |(column, _)| (row, column))
+            })
+            .filter(|cell| paths.through(*cell).is_some_and(|p| p.len() <= max_len))
+            .collect();
+
+        let mut words = Vec::with_capacity(count);
+        while words.len() < count {
+            if uncovered.is_empty() {
+                let len = lengths[rng.gen_range(0..lengths.len())];
+                words.extend(self.sample_from(&targets, &counts, len, rng));
+                continue;
+            }
+            let cell = uncovered[rng.gen_range(0..uncovered.len())];
+            let path = paths.through(cell).expect("uncovered cells have a path");
+            let taken: BTreeSet<(usize, usize)> = path.iter().copied().collect();
+            uncovered.retain(|cell| !taken.contains(cell));
+            words.push(path.iter().map(|(_, c)| self.alphabet()[*c]).collect());
+        }
+        words
+    }
+
+    /// Up to `count` distinct strings the table rejects that are one edit, an inserted, deleted
+    /// or replaced symbol of the alphabet, away from an accepted string of at most `max_len`
+    /// characters from [`LabeledTable::generate_accepted`]. Near misses like these exercise
+    /// error handling better than arbitrary garbage. Gives up after a bounded number of
+    /// attempts, so languages with few near misses yield fewer strings.
+    #[cfg(feature = "rand")]
+    pub fn generate_rejected<R: rand::Rng + ?Sized>(
+        &self,
+        count: usize,
+        max_len: usize,
+        rng: &mut R,
+    ) -> Vec<String> {
+        let accepted = self.generate_accepted(count.max(1), max_len, rng);
+        if accepted.is_empty() || self.alphabet().is_empty() {
+            return Vec::new();
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut words = Vec::with_capacity(count);
+        for _ in 0..count.saturating_mul(64) {
+            if words.len() == count {
+                break;
+            }
+            let mut word: Vec<char> = accepted[rng.gen_range(0..accepted.len())].chars().collect();
+            let symbol = self.alphabet()[rng.gen_range(0..self.alphabet().len())];
+            match rng.gen_range(0..3) {
+                0 => word.insert(rng.gen_range(0..word.len() + 1), symbol),
+                _ if word.is_empty() => continue,
+                1 => {
+                    word.remove(rng.gen_range(0..word.len()));
+                }
+                _ => {
+                    let at = rng.gen_range(0..word.len());
+                    word[at] = symbol;
+                }
+            }
+            let word: String = word.into_iter().collect();
+            let mut run = DfaRun::new(self, &word);
+            run.finish();
+            if !run.accepted() && seen.insert(word.clone()) {
+                words.push(word);
+            }
+        }
+        words
+    }
+}
+
+/// Breadth-first trees towards and away from the start state, for the shortest accepted path
+/// through any one transition
+#[cfg(feature = "rand")]
+struct ShortestPaths<'a> {
+    targets: &'a [Vec<Option<usize>>],
+    /// Previous `(row, column)` on a shortest path from the start, `None` for the start itself
+    /// and unreachable rows alike
+    from_start: Vec<Option<(usize, usize)>>,
+    reachable: Vec<bool>,
+    /// Next column on a shortest path to an accepting row, `None` when already accepting or
+    /// when no accepting row can be reached
+    to_accept: Vec<Option<usize>>,
+    live: Vec<bool>,
+}
+
+#[cfg(feature = "rand")]
+impl<'a> ShortestPaths<'a> {
+    fn new(table: &LabeledTable, targets: &'a [Vec<Option<usize>>]) -> Self {
+        let rows = targets.len();
+        let mut from_start = vec![None; rows];
+        let mut reachable = vec![false; rows];
+        let mut queue = VecDeque::new();
+        if rows > 0 {
+            reachable[0] = true;
+            queue.push_back(0);
+        }
+        while let Some(row) = queue.pop_front() {
+            for (column, target) in targets[row].iter().enumerate() {
+                if let Some(next) = *target
+                    && !reachable[next]
+                {
+                    reachable[next] = true;
+                    from_start[next] = Some((row, column));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut predecessors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); rows];
+        for (row, cells) in targets.iter().enumerate() {
+            for (column, target) in cells.iter().enumerate() {
+                if let Some(next) = *target {
+                    predecessors[next].push((row, column));
+                }
+            }
+        }
+        let mut to_accept = vec![None; rows];
+        let mut live: Vec<bool> = table.table().rows.iter().map(|r| r.accepting).collect();
+        let mut queue: VecDeque<usize> = (0..rows).filter(|row| live[*row]).collect();
+        while let Some(row) = queue.pop_front() {
+            for (previous, column) in &predecessors[row] {
+                if !live[*previous] {
+                    live[*previous] = true;
+                    to_accept[*previous] = Some(*column);
+                    queue.push_back(*previous);
+                }
+            }
+        }
+
+        Self {
+            targets,
+            from_start,
+            reachable,
+            to_accept,
+            live,
+        }
+    }
+
+    /// The `(row, column)` cells of the shortest accepted path taking `cell`, if there is one
+    fn through(&self, (row, column): (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let next = self.targets[row][column]?;
+        if !self.reachable[row] || !self.live[next] {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut current = row;
+        while let Some(previous) = self.from_start[current] {
+            path.push(previous);
+            current = previous.0;
+        }
+        path.reverse();
+        path.push((row, column));
+        let mut current = next;
+        while let Some(column) = self.to_accept[current] {
+            path.push((current, column));
+            current = self.targets[current][column].expect("paths follow transitions");
+        }
+        Some(path)
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Display,
+};
+
+use crate::{Automaton, FiniteAutomaton, LabeledTable, NFA};
+
+type State = usize;
+type Adjacency = BTreeMap<State, BTreeSet<State>>;
+
+pub struct Stats {
+    pub states: usize,
+    pub alphabet_size: usize,
+    /// Counted per (state, symbol, target), lambda included
+    pub transitions: usize,
+    pub accepting: usize,
+    pub unreachable: BTreeSet<State>,
+    pub dead: BTreeSet<State>,
+    pub strongly_connected_components: usize,
+    /// Longest shortest path between two states, ignoring labels
+    pub diameter: usize,
+    /// Approximate heap usage of the measured representation in bytes
+    pub memory_footprint: usize,
+}
+
+impl Stats {
+    /// Transitions per (state, symbol) cell, 1.0 for a complete DFA
+    pub fn density(&self) -> f64 {
+        let cells = self.states * self.alphabet_size;
+        match cells {
+            0 => 0.0,
+            _ => self.transitions as f64 / cells as f64,
+        }
+    }
+}
+
+fn distances(adjacency: &Adjacency, from: State) -> BTreeMap<State, usize> {
+    let mut dist = BTreeMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(n) = queue.pop_front() {
+        let d = dist[&n];
+        for next in adjacency.get(&n).into_iter().flatten() {
+            if !dist.contains_key(next) {
+                dist.insert(*next, d + 1);
+                queue.push_back(*next);
+            }
+        }
+    }
+    dist
+}
+
+/// Kosaraju's algorithm, iterative to survive large automata
+fn count_sccs(nodes: &BTreeSet<State>, adjacency: &Adjacency, reverse: &Adjacency) -> usize {
+    let mut visited = BTreeSet::new();
+    let mut order = Vec::new();
+    for root in nodes {
+        if !visited.insert(*root) {
+            continue;
+        }
+        let mut stack = vec![(*root, adjacency.get(root).into_iter().flatten())];
+        while let Some((node, successors)) = stack.last_mut() {
+            match successors.find(|n| !visited.contains(*n)) {
+                Some(next) => {
+                    visited.insert(*next);
+                    stack.push((*next, adjacency.get(next).into_iter().flatten()));
+                }
+                None => {
+                    order.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    // Each search stops at nodes of earlier components, so it collects exactly one component
+    let mut assigned = BTreeSet::new();
+    let mut components = 0;
+    for root in order.into_iter().rev() {
+        if !assigned.insert(root) {
+            continue;
+        }
+        components += 1;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            for previous in reverse.get(&node).into_iter().flatten() {
+                if assigned.insert(*previous) {
+                    stack.push(*previous);
+                }
+            }
+        }
+    }
+    components
+}
+
+impl Stats {
+    /// Everything but the memory footprint, which depends on the representation
+    fn measure(automaton: &impl FiniteAutomaton, memory_footprint: usize) -> Self {
+        let nodes = automaton.state_ids();
+        let mut adjacency: Adjacency = nodes.iter().map(|s| (*s, BTreeSet::new())).collect();
+        let mut reverse = Adjacency::new();
+        let edges = automaton.edges();
+        for (from, _, to) in &edges {
+            adjacency.entry(*from).or_default().insert(*to);
+            reverse.entry(*to).or_default().insert(*from);
+        }
+
+        Self {
+            states: nodes.len(),
+            alphabet_size: automaton.symbols().len(),
+            transitions: edges.len(),
+            accepting: nodes
+                .iter()
+                .filter(|s| automaton.is_accepting_state(**s))
+                .count(),
+            unreachable: nodes
+                .difference(&automaton.reachable_states())
+                .copied()
+                .collect(),
+            dead: nodes
+                .difference(&automaton.live_states())
+                .copied()
+                .collect(),
+            strongly_connected_components: count_sccs(&nodes, &adjacency, &reverse),
+            diameter: nodes
+                .iter()
+                .filter_map(|n| distances(&adjacency, *n).into_values().max())
+                .max()
+                .unwrap_or_default(),
+            memory_footprint,
+        }
+    }
+}
+
+impl From<&NFA> for Stats {
+    fn from(value: &NFA) -> Self {
+        Stats::measure(value, value.memory_footprint())
+    }
+}
+
+impl From<&LabeledTable> for Stats {
+    fn from(value: &LabeledTable) -> Self {
+        Stats::measure(value, value.memory_footprint())
+    }
+}
+
+impl From<&Automaton> for Stats {
+    fn from(value: &Automaton) -> Self {
+        Stats::measure(value, value.memory_footprint())
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |set: &BTreeSet<State>| {
+            set.iter()
+                .map(|s| s.to_string())
+                .reduce(|acc, s| acc + " " + &s)
+                .unwrap_or_default()
+        };
+        writeln!(f, "states: {}", self.states)?;
+        writeln!(f, "alphabet size: {}", self.alphabet_size)?;
+        writeln!(f, "transitions: {}", self.transitions)?;
+        writeln!(f, "transition density: {:.3}", self.density())?;
+        writeln!(f, "accepting states: {}", self.accepting)?;
+        writeln!(
+            f,
+            "unreachable states: {} [{}]",
+            self.unreachable.len(),
+            list(&self.unreachable)
+        )?;
+        writeln!(f, "dead states: {} [{}]", self.dead.len(), list(&self.dead))?;
+        writeln!(
+            f,
+            "strongly connected components: {}",
+            self.strongly_connected_components
+        )?;
+        writeln!(f, "diameter: {}", self.diameter)?;
+        write!(f, "memory footprint: ~{} bytes", self.memory_footprint)
+    }
+}
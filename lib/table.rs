@@ -0,0 +1,1046 @@
+use alphabet_encoding::decode;
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use transition_tables::{TransitionTable, TransitionTableRow};
+
+use crate::{
+    FiniteAutomaton, LabelStyle, Transition,
+    digraph::escape,
+    import::{assign_ids, labels},
+    nfa::named_symbol,
+    tokens::{self, Tokens},
+    validate::debug_validate,
+};
+
+/// Symbol used for column `i` of a table parsed without an alphabet
+pub(crate) fn column_symbol(i: usize) -> char {
+    char::from_u32(i as u32 + 'a' as u32).expect("Unable to convert from decimal to char.")
+}
+
+/// Operations on transition tables beyond what `transition_tables` provides.
+pub trait TransitionTableExt {
+    /// Number of columns, the widest row if rows disagree
+    fn alphabet_size(&self) -> usize;
+
+    /// Replaces every missing transition with a new non-accepting trap state.
+    /// Returns the id of the trap, or `None` if the table was already complete.
+    fn complete(&mut self) -> Option<usize>;
+
+    /// Approximate bytes used, rows times alphabet times id width plus row headers
+    fn memory_footprint(&self) -> usize;
+
+    /// Aligned box-drawing table with a header of `alphabet` and `+`/`−` accepting markers.
+    /// Columns beyond the alphabet are labeled like tables parsed without one.
+    fn pretty_print(&self, alphabet: &[char]) -> String;
+
+    /// [`TransitionTableExt::pretty_print`] as a Markdown table
+    fn to_markdown(&self, alphabet: &[char]) -> String;
+
+    /// [`TransitionTableExt::pretty_print`] as CSV, a header line and one line per row
+    fn to_csv(&self, alphabet: &[char]) -> String;
+
+    /// Renumbers states in BFS order from the start state, the first row, following columns
+    /// left to right, so isomorphic tables come out identical. Unreachable states follow in
+    /// their original order. Rows are sorted by the new ids, returns the map from old to new ids.
+    fn canonicalize(&mut self) -> BTreeMap<usize, usize>;
+
+    /// The row of state `id`
+    fn row(&self, id: usize) -> Option<&TransitionTableRow>;
+
+    /// Appends `row`, refusing a second row for the same state
+    fn add_row(&mut self, row: TransitionTableRow) -> Result<(), BuildError>;
+
+    /// Removes the row of state `id`, transitions into it become missing transitions.
+    /// Returns the removed row.
+    fn remove_state(&mut self, id: usize) -> Option<TransitionTableRow>;
+
+    /// Renumbers states by `map`, states it leaves out keep their id. Fails without changing
+    /// anything if two states would end up with the same id.
+    fn relabel(&mut self, map: &BTreeMap<usize, usize>) -> Result<(), BuildError>;
+
+    /// Every `(state, column)` with a transition into `id`, in row order
+    fn transitions_into(&self, id: usize) -> Vec<(usize, usize)>;
+
+    /// States reachable from the start state, the first row, and states that can reach an
+    /// accepting one, from one forward and one backward search over the whole table
+    fn reachability(&self) -> Reachability;
+}
+
+/// Result of [`TransitionTableExt::reachability`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Reachability {
+    pub reachable: BTreeSet<usize>,
+    pub can_accept: BTreeSet<usize>,
+}
+
+impl Reachability {
+    /// Reachable and able to accept, removing any other state keeps the language
+    pub fn is_useful(&self, id: usize) -> bool {
+        self.reachable.contains(&id) && self.can_accept.contains(&id)
+    }
+}
+
+/// Draws `rows` below `header` with box-drawing borders, every column as wide as its widest cell
+fn box_table(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let columns = std::iter::once(&header)
+        .chain(&rows)
+        .map(Vec::len)
+        .max()
+        .unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .filter_map(|r| r.get(i))
+                .map(|c| c.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let rule = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}\n", left, segments.join(middle), right)
+    };
+    let line = |cells: &Vec<String>| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let cell = cells.get(i).map_or("", String::as_str);
+                format!(" {}{} ", cell, " ".repeat(w - cell.chars().count()))
+            })
+            .collect();
+        format!("│{}│\n", cells.join("│"))
+    };
+
+    let mut out = rule("┌", "┬", "┐");
+    out += &line(&header);
+    out += &rule("├", "┼", "┤");
+    for row in &rows {
+        out += &line(row);
+    }
+    out + &rule("└", "┴", "┘")
+}
+
+/// One GitHub-flavored Markdown table row per row, `|` in cells escaped
+fn markdown_table(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let line = |cells: &Vec<String>| {
+        let cells: Vec<String> = cells.iter().map(|c| c.replace('|', "\\|")).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = line(&header);
+    out += &format!("|{}\n", " --- |".repeat(header.len()));
+    for row in &rows {
+        out += &line(row);
+    }
+    out
+}
+
+/// RFC 4180 CSV, cells quoted when they contain a comma, quote or line break
+fn csv_table(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            let cells: Vec<String> = row
+                .into_iter()
+                .map(|c| match c.contains([',', '"', '\n', '\r']) {
+                    true => format!("\"{}\"", c.replace('"', "\"\"")),
+                    false => c,
+                })
+                .collect();
+            cells.join(",") + "\r\n"
+        })
+        .collect()
+}
+
+/// How tables meant for reading are written out
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TableFormat {
+    /// Box-drawing borders for a terminal
+    #[default]
+    Text,
+    Markdown,
+    Csv,
+}
+
+impl TableFormat {
+    pub(crate) fn render(self, header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+        match self {
+            TableFormat::Text => box_table(header, rows),
+            TableFormat::Markdown => markdown_table(header, rows),
+            TableFormat::Csv => csv_table(header, rows),
+        }
+    }
+}
+
+fn accepting_marker(accepting: bool) -> String {
+    match accepting {
+        true => "+".to_owned(),
+        false => "−".to_owned(),
+    }
+}
+
+impl TransitionTableExt for TransitionTable {
+    fn alphabet_size(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|r| r.transitions.len())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn complete(&mut self) -> Option<usize> {
+        let width = self.alphabet_size();
+        let is_complete = self
+            .rows
+            .iter()
+            .all(|r| r.transitions.len() == width && r.transitions.iter().all(Option::is_some));
+        if is_complete {
+            return None;
+        }
+
+        let trap = self.rows.iter().map(|r| r.id + 1).max().unwrap_or_default();
+        for row in &mut self.rows {
+            row.transitions.resize(width, None);
+            row.transitions
+                .iter_mut()
+                .filter(|t| t.is_none())
+                .for_each(|t| *t = Some(trap));
+        }
+        self.rows.push(TransitionTableRow {
+            id: trap,
+            accepting: false,
+            transitions: vec![Some(trap); width],
+        });
+
+        Some(trap)
+    }
+
+    fn canonicalize(&mut self) -> BTreeMap<usize, usize> {
+        let rows: BTreeMap<usize, &TransitionTableRow> =
+            self.rows.iter().map(|r| (r.id, r)).collect();
+        let mut ids: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        let visit = |id: usize, ids: &mut BTreeMap<usize, usize>, queue: &mut VecDeque<usize>| {
+            if !ids.contains_key(&id) {
+                ids.insert(id, ids.len());
+                queue.push_back(id);
+            }
+        };
+        // Unreachable rows start a new search in their original order once the queue runs dry
+        for start in self.rows.iter().map(|r| r.id) {
+            visit(start, &mut ids, &mut queue);
+            while let Some(id) = queue.pop_front() {
+                let targets = rows
+                    .get(&id)
+                    .into_iter()
+                    .flat_map(|r| r.transitions.iter().flatten());
+                for target in targets {
+                    visit(*target, &mut ids, &mut queue);
+                }
+            }
+        }
+
+        self.relabel(&ids)
+            .expect("BFS numbering gives every state its own id");
+        self.rows.sort_by_key(|r| r.id);
+        ids
+    }
+
+    fn row(&self, id: usize) -> Option<&TransitionTableRow> {
+        self.rows.iter().find(|r| r.id == id)
+    }
+
+    fn add_row(&mut self, row: TransitionTableRow) -> Result<(), BuildError> {
+        if self.row(row.id).is_some() {
+            return Err(BuildError::DuplicateState(row.id));
+        }
+        self.rows.push(row);
+        Ok(())
+    }
+
+    fn remove_state(&mut self, id: usize) -> Option<TransitionTableRow> {
+        let position = self.rows.iter().position(|r| r.id == id)?;
+        let row = self.rows.remove(position);
+        for t in self.rows.iter_mut().flat_map(|r| r.transitions.iter_mut()) {
+            if *t == Some(id) {
+                *t = None;
+            }
+        }
+        Some(row)
+    }
+
+    fn relabel(&mut self, map: &BTreeMap<usize, usize>) -> Result<(), BuildError> {
+        let new_id = |id: usize| map.get(&id).copied().unwrap_or(id);
+        let mut ids = BTreeSet::new();
+        if let Some(row) = self.rows.iter().find(|r| !ids.insert(new_id(r.id))) {
+            return Err(BuildError::DuplicateState(new_id(row.id)));
+        }
+
+        for row in &mut self.rows {
+            row.id = new_id(row.id);
+            row.transitions
+                .iter_mut()
+                .flatten()
+                .for_each(|t| *t = new_id(*t));
+        }
+        Ok(())
+    }
+
+    fn reachability(&self) -> Reachability {
+        Reachability {
+            reachable: self.reachable_states(),
+            can_accept: self.live_states(),
+        }
+    }
+
+    fn transitions_into(&self, id: usize) -> Vec<(usize, usize)> {
+        self.rows
+            .iter()
+            .flat_map(|r| {
+                r.transitions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| **t == Some(id))
+                    .map(|(column, _)| (r.id, column))
+            })
+            .collect()
+    }
+
+    fn memory_footprint(&self) -> usize {
+        size_of::<TransitionTable>()
+            + self.rows.capacity() * size_of::<TransitionTableRow>()
+            + self
+                .rows
+                .iter()
+                .map(|r| r.transitions.capacity() * size_of::<Option<usize>>())
+                .sum::<usize>()
+    }
+
+    fn pretty_print(&self, alphabet: &[char]) -> String {
+        let (header, rows) = table_cells(self, alphabet);
+        TableFormat::Text.render(header, rows)
+    }
+
+    fn to_markdown(&self, alphabet: &[char]) -> String {
+        let (header, rows) = table_cells(self, alphabet);
+        TableFormat::Markdown.render(header, rows)
+    }
+
+    fn to_csv(&self, alphabet: &[char]) -> String {
+        let (header, rows) = table_cells(self, alphabet);
+        TableFormat::Csv.render(header, rows)
+    }
+}
+
+/// Header and rows of [`TransitionTableExt::pretty_print`]
+fn table_cells(table: &TransitionTable, alphabet: &[char]) -> (Vec<String>, Vec<Vec<String>>) {
+    let header = [String::new(), String::new()]
+        .into_iter()
+        .chain((0..table.alphabet_size()).map(|i| {
+            let c = alphabet.get(i).copied().unwrap_or(column_symbol(i));
+            Transition::Char(c).render(&LabelStyle::text())
+        }))
+        .collect();
+    let rows = table
+        .rows
+        .iter()
+        .map(|r| {
+            [accepting_marker(r.accepting), r.id.to_string()]
+                .into_iter()
+                .chain(
+                    r.transitions
+                        .iter()
+                        .map(|t| t.map_or("∅".to_owned(), |t| t.to_string())),
+                )
+                .collect()
+        })
+        .collect();
+    (header, rows)
+}
+
+/// A transition table together with the symbol of each column
+pub struct LabeledTable {
+    alphabet: Vec<char>,
+    table: TransitionTable,
+    /// Moore machine outputs, keyed by state id
+    pub state_outputs: BTreeMap<usize, String>,
+    /// Mealy machine outputs, keyed by state id and column
+    pub transition_outputs: BTreeMap<(usize, usize), String>,
+    /// Names of states that were defined by name rather than number
+    pub state_labels: BTreeMap<usize, String>,
+    /// Names of the symbols of more than one character, see [`crate::NFA::tokens`]
+    pub tokens: BTreeMap<char, String>,
+}
+
+impl LabeledTable {
+    pub fn new(alphabet: Vec<char>, table: TransitionTable) -> Result<Self, BuildError> {
+        match table
+            .rows
+            .iter()
+            .find(|r| r.transitions.len() > alphabet.len())
+        {
+            Some(row) => Err(BuildError::TooManyColumns(row.id)),
+            None => Ok(Self {
+                alphabet,
+                table,
+                state_outputs: BTreeMap::new(),
+                transition_outputs: BTreeMap::new(),
+                state_labels: BTreeMap::new(),
+                tokens: Tokens::new(),
+            }),
+        }
+    }
+
+    pub fn alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    pub fn table(&self) -> &TransitionTable {
+        &self.table
+    }
+
+    pub fn memory_footprint(&self) -> usize {
+        self.table.memory_footprint()
+            + size_of::<Vec<char>>()
+            + self.alphabet.capacity() * size_of::<char>()
+    }
+
+    /// Row index of the target of every cell, `None` for missing transitions and targets
+    /// without a row
+    pub(crate) fn target_rows(&self) -> Vec<Vec<Option<usize>>> {
+        let rows = &self.table.rows;
+        rows.iter()
+            .map(|r| {
+                r.transitions
+                    .iter()
+                    .map(|t| t.and_then(|t| rows.iter().position(|r| r.id == t)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Label for the transition out of `state` on `column`, `a/1` style if it has a Mealy output
+    pub fn edge_label(&self, state: usize, column: usize) -> String {
+        let symbol = self.symbol_name(column, &LabelStyle::dot());
+        match self.transition_outputs.get(&(state, column)) {
+            Some(output) => format!("{}/{}", symbol, escape(output)),
+            None => symbol,
+        }
+    }
+
+    /// The symbol of `column` spelled for `style`, tokens by name
+    pub fn symbol_name(&self, column: usize, style: &LabelStyle) -> String {
+        tokens::render(
+            &self.tokens,
+            &Transition::Char(self.alphabet[column]),
+            style,
+        )
+    }
+
+    /// The input standing for the token stream `input`, see [`crate::NFA::spell`]
+    pub fn spell(&self, input: &[&str]) -> Option<String> {
+        tokens::spell(&self.tokens, input)
+    }
+
+    /// The name a state was defined with, or its id
+    pub fn state_name(&self, id: usize) -> String {
+        self.state_labels
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Parses a transition table, every row is `<marker> <id> <targets...>`. A state written
+    /// `id/out` has a Moore output and a cell written `target/out` has a Mealy output.
+    ///
+    /// Outside of strict mode, a first line starting with `#` lists the alphabet, as does one
+    /// whose first field cannot start a row. Rows may be marked `1`/`0` instead of `+`/`-`, any of
+    /// [`ParseOptions::dead_symbols`] stands for a missing transition, and states may be named
+    /// instead of numbered. Named states are numbered in order of appearance.
+    ///
+    /// ```text
+    /// #        a    b
+    /// - 0/idle 1/x  -
+    /// + 1/busy 1    0/y
+    /// ```
+    pub fn parse(s: &str, options: &ParseOptions) -> Result<LabeledTable, TableParseError> {
+        fn split_output(field: &str) -> (&str, Option<String>) {
+            match field.split_once('/') {
+                Some((value, output)) => (value, Some(output.to_owned())),
+                None => (field, None),
+            }
+        }
+
+        let mut lines = s.lines().filter(|l| !l.trim().is_empty()).peekable();
+        let alphabet = match lines.peek() {
+            Some(line) if !options.strict && is_header(line) => {
+                let header = parse_header(line)?;
+                lines.next();
+                Some(header)
+            }
+            _ => None,
+        };
+
+        type RawRow = (
+            bool,
+            String,
+            Option<String>,
+            Vec<(Option<String>, Option<String>)>,
+        );
+        let mut raw_rows: Vec<RawRow> = Vec::new();
+        for line in lines {
+            let mut fields = line.split_whitespace();
+            let accepting = match (fields.next(), options.strict) {
+                (Some("+"), _) => true,
+                (Some("-"), _) => false,
+                (Some("1"), false) => true,
+                (Some("0"), false) => false,
+                _ => return Err(TableParseError::InvalidAcceptingMarker),
+            };
+            let (name, output) =
+                split_output(fields.next().ok_or(TableParseError::MissingStateId)?);
+            if options.strict && name.parse::<usize>().is_err() {
+                return Err(TableParseError::InvalidStateId);
+            }
+
+            let cells = fields
+                .map(|cell| {
+                    let (target, output) = split_output(cell);
+                    match target {
+                        t if options.dead_symbols.iter().any(|d| d == t) => Ok((None, output)),
+                        t if options.strict && t.parse::<usize>().is_err() => {
+                            Err(TableParseError::InvalidTarget)
+                        }
+                        t => Ok((Some(t.to_owned()), output)),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let width = match (&alphabet, raw_rows.first()) {
+                (Some((alphabet, _)), _) => Some(alphabet.len()),
+                (None, Some(first)) if options.strict => Some(first.3.len()),
+                _ => None,
+            };
+            let too_wide = alphabet.is_some() && width.is_some_and(|w| cells.len() > w);
+            let uneven = options.strict && width.is_some_and(|w| cells.len() != w);
+            if too_wide || uneven {
+                return Err(TableParseError::RowWidthMismatch(name.to_owned()));
+            }
+
+            raw_rows.push((accepting, name.to_owned(), output, cells));
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        for (_, name, _, cells) in &raw_rows {
+            for name in std::iter::once(name).chain(cells.iter().filter_map(|c| c.0.as_ref())) {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        let ids = assign_ids(&names, None);
+        let state_labels = labels(&ids);
+
+        let mut rows: Vec<TransitionTableRow> = Vec::new();
+        let mut state_outputs = BTreeMap::new();
+        let mut transition_outputs = BTreeMap::new();
+        for (accepting, name, output, cells) in raw_rows {
+            let id = ids[&name];
+            if let Some(output) = output {
+                state_outputs.insert(id, output);
+            }
+            let transitions = cells
+                .into_iter()
+                .enumerate()
+                .map(|(column, (target, output))| {
+                    if let Some(output) = output {
+                        transition_outputs.insert((id, column), output);
+                    }
+                    target.map(|t| ids[&t])
+                })
+                .collect();
+            rows.push(TransitionTableRow {
+                id,
+                accepting,
+                transitions,
+            });
+        }
+
+        let table = TransitionTable { rows };
+        let mut table = match alphabet {
+            Some((alphabet, tokens)) => LabeledTable {
+                alphabet,
+                table,
+                state_outputs: BTreeMap::new(),
+                transition_outputs: BTreeMap::new(),
+                state_labels: BTreeMap::new(),
+                tokens,
+            },
+            None => LabeledTable::from(table),
+        };
+        table.state_outputs = state_outputs;
+        table.transition_outputs = transition_outputs;
+        table.state_labels = state_labels;
+        Ok(table)
+    }
+
+    pub fn serialize(&self, options: &SerializeOptions) -> String {
+        let with_output = |value: String, output: Option<&String>| match output {
+            Some(output) => format!("{}/{}", value, output),
+            None => value,
+        };
+
+        // An empty cell would vanish between the whitespace-separated fields
+        let dead_symbol = match options.dead_symbol.trim() {
+            "" => "-",
+            dead => dead,
+        };
+
+        let mut grid: Vec<Vec<String>> = Vec::new();
+        if options.header {
+            grid.push(
+                [HEADER_MARKER.to_owned(), String::new()]
+                    .into_iter()
+                    .chain(
+                        (0..self.alphabet.len())
+                            .map(|column| self.symbol_name(column, &LabelStyle::text())),
+                    )
+                    .collect(),
+            );
+        }
+        for row in &self.table.rows {
+            let marker = match (options.accepting_markers, row.accepting) {
+                (AcceptingMarkers::PlusMinus, true) => "+",
+                (AcceptingMarkers::PlusMinus, false) => "-",
+                (AcceptingMarkers::Binary, true) => "1",
+                (AcceptingMarkers::Binary, false) => "0",
+            };
+            grid.push(
+                [
+                    marker.to_owned(),
+                    with_output(self.state_name(row.id), self.state_outputs.get(&row.id)),
+                ]
+                .into_iter()
+                .chain((0..self.alphabet.len()).map(|column| {
+                    let target = match row.transitions.get(column).copied().flatten() {
+                        Some(target) => self.state_name(target),
+                        None => dead_symbol.to_owned(),
+                    };
+                    with_output(target, self.transition_outputs.get(&(row.id, column)))
+                }))
+                .collect(),
+            );
+        }
+
+        let widths: Vec<usize> = (0..self.alphabet.len() + 2)
+            .map(|column| {
+                grid.iter()
+                    .filter_map(|r| r.get(column))
+                    .map(|c| c.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        grid.iter()
+            .map(|r| {
+                let cells = r
+                    .iter()
+                    .zip(&widths)
+                    .filter(|(cell, _)| options.align || !cell.is_empty())
+                    .map(|(cell, width)| match options.align {
+                        true => format!("{:<width$}", cell, width = width),
+                        false => cell.clone(),
+                    });
+                let line = Itertools::intersperse(cells, " ".to_owned()).collect::<String>();
+                line.trim_end().to_owned()
+            })
+            .map(|l| l + "\n")
+            .collect()
+    }
+
+    /// [`TransitionTableExt::pretty_print`] with this table's alphabet and transducer outputs
+    pub fn pretty_print(&self) -> String {
+        self.render(TableFormat::Text)
+    }
+
+    /// The table of [`LabeledTable::pretty_print`] in any [`TableFormat`]
+    pub fn render(&self, format: TableFormat) -> String {
+        let header = [String::new(), String::new()]
+            .into_iter()
+            .chain(
+                (0..self.alphabet.len())
+                    .map(|column| self.symbol_name(column, &LabelStyle::text())),
+            )
+            .collect();
+        let with_output = |value: String, output: Option<&String>| match output {
+            Some(output) => format!("{}/{}", value, output),
+            None => value,
+        };
+        let rows = self
+            .table
+            .rows
+            .iter()
+            .map(|r| {
+                [
+                    accepting_marker(r.accepting),
+                    with_output(self.state_name(r.id), self.state_outputs.get(&r.id)),
+                ]
+                .into_iter()
+                .chain(r.transitions.iter().enumerate().map(|(column, t)| {
+                    with_output(
+                        t.map_or("∅".to_owned(), |t| self.state_name(t)),
+                        self.transition_outputs.get(&(r.id, column)),
+                    )
+                }))
+                .collect()
+            })
+            .collect();
+        format.render(header, rows)
+    }
+
+    /// Orders rows by state id, the first row stays first since it is the start state
+    pub fn sort_rows(&mut self) {
+        if let Some((_, rest)) = self.table.rows.split_first_mut() {
+            rest.sort_by_key(|r| r.id);
+        }
+    }
+
+    pub fn into_table(self) -> TransitionTable {
+        self.table
+    }
+
+    /// Leftmost-longest non-empty match anywhere in `haystack`, starting from the first row
+    pub fn find(&self, haystack: &[char]) -> Option<RangeInclusive<usize>> {
+        self.find_iter(haystack).next()
+    }
+
+    /// Every non-overlapping match in `haystack` from left to right, see [`LabeledTable::find`]
+    pub fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [char],
+    ) -> impl Iterator<Item = RangeInclusive<usize>> + 'a {
+        let rows: BTreeMap<usize, &TransitionTableRow> =
+            self.table.rows.iter().map(|r| (r.id, r)).collect();
+        let longest = move |from: usize| {
+            let mut state = self.table.rows.first()?.id;
+            let mut end = None;
+            for (i, c) in haystack.iter().enumerate().skip(from) {
+                let column = self.alphabet.iter().position(|a| a == c);
+                match column.and_then(|column| rows.get(&state)?.transitions.get(column)?.as_ref())
+                {
+                    Some(next) => state = *next,
+                    None => break,
+                }
+                if rows.get(&state).is_some_and(|r| r.accepting) {
+                    end = Some(i);
+                }
+            }
+            end
+        };
+
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            while position < haystack.len() {
+                match longest(position) {
+                    Some(end) => {
+                        let found = position..=end;
+                        position = end + 1;
+                        return Some(found);
+                    }
+                    None => position += 1,
+                }
+            }
+            None
+        })
+    }
+
+    /// See [`TransitionTableExt::complete`], rows are padded to the full alphabet first
+    pub fn complete(&mut self) -> Option<usize> {
+        let width = self.alphabet.len();
+        self.table
+            .rows
+            .iter_mut()
+            .for_each(|r| r.transitions.resize(width, None));
+        let trap = self.table.complete();
+        debug_validate(self, "complete");
+        trap
+    }
+}
+
+impl LabeledTable {
+    /// See [`TransitionTableExt::canonicalize`], outputs and state names follow their states
+    pub fn canonicalize(&mut self) {
+        let ids = self.table.canonicalize();
+        self.state_outputs = std::mem::take(&mut self.state_outputs)
+            .into_iter()
+            .map(|(state, output)| (ids[&state], output))
+            .collect();
+        self.transition_outputs = std::mem::take(&mut self.transition_outputs)
+            .into_iter()
+            .map(|((state, column), output)| ((ids[&state], column), output))
+            .collect();
+        self.state_labels = std::mem::take(&mut self.state_labels)
+            .into_iter()
+            .map(|(state, label)| (ids[&state], label))
+            .collect();
+        debug_validate(self, "canonicalize");
+    }
+}
+
+/// Columns are named `a`, `b`, ... like the plain table format implies
+impl From<TransitionTable> for LabeledTable {
+    fn from(table: TransitionTable) -> Self {
+        let alphabet = (0..table.alphabet_size()).map(column_symbol).collect();
+        Self {
+            alphabet,
+            table,
+            state_outputs: BTreeMap::new(),
+            transition_outputs: BTreeMap::new(),
+            state_labels: BTreeMap::new(),
+            tokens: Tokens::new(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AcceptingMarkers {
+    /// `+` for accepting rows, `-` otherwise
+    #[default]
+    PlusMinus,
+    /// `1` for accepting rows, `0` otherwise
+    Binary,
+}
+
+/// Output format of [`LabeledTable::serialize`], the default matches the plain table format
+#[derive(Debug, Clone)]
+pub struct SerializeOptions {
+    /// Written for missing transitions, `-` if empty since an empty cell could not be read back
+    pub dead_symbol: String,
+    /// Pad every column to the same width
+    pub align: bool,
+    /// Start with a line listing the alphabet, marked with `#`
+    pub header: bool,
+    pub accepting_markers: AcceptingMarkers,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            dead_symbol: "E".to_owned(),
+            align: false,
+            header: false,
+            accepting_markers: AcceptingMarkers::default(),
+        }
+    }
+}
+
+/// Knobs for [`LabeledTable::parse`], the default accepts every supported variation
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Only accept `+`/`-` markers, no alphabet header and rows of equal width
+    pub strict: bool,
+    /// Cells that stand for a missing transition
+    pub dead_symbols: Vec<String>,
+}
+
+impl ParseOptions {
+    /// The plain table format, `E` for missing transitions and nothing else
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            dead_symbols: vec!["E".to_owned()],
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            dead_symbols: ["E", "-", "X"].map(str::to_owned).to_vec(),
+        }
+    }
+}
+
+/// Starts the alphabet line written by [`LabeledTable::serialize`]
+const HEADER_MARKER: &str = "#";
+
+/// The alphabet line is marked with [`HEADER_MARKER`]. An unmarked line counts as one only if
+/// its first field could not start a row, so a header of `0 1` needs the marker.
+fn is_header(line: &str) -> bool {
+    !matches!(line.split_whitespace().next(), Some("+" | "-" | "1" | "0"))
+}
+
+/// The alphabet of a header line, symbols of more than one character interned as tokens
+fn parse_header(line: &str) -> Result<(Vec<char>, Tokens), TableParseError> {
+    let line = line.trim_start();
+    let line = match line.strip_prefix(HEADER_MARKER) {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest,
+        _ => line,
+    };
+    let mut tokens = Tokens::new();
+    let alphabet = line
+        .split_whitespace()
+        .map(|symbol| match symbol {
+            s if let Some(c) = named_symbol(s) => Ok(c),
+            s => decode(s.to_string())
+                .ok()
+                .and_then(|decoded| tokens::intern(&mut tokens, &decoded))
+                .ok_or_else(|| TableParseError::InvalidHeaderSymbol(s.to_owned())),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((alphabet, tokens))
+}
+
+#[derive(Debug)]
+pub enum TableParseError {
+    InvalidAcceptingMarker,
+    MissingStateId,
+    InvalidStateId,
+    InvalidTarget,
+    InvalidHeaderSymbol(String),
+    /// The row is wider than the alphabet header, or than the first row in strict mode
+    RowWidthMismatch(String),
+}
+impl Error for TableParseError {}
+impl Display for TableParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableParseError::InvalidAcceptingMarker => {
+                write!(f, "InvalidAcceptingMarker: Row does not start with + or -!")
+            }
+            TableParseError::MissingStateId => write!(f, "MissingStateId: Row has no state id!"),
+            TableParseError::InvalidStateId => {
+                write!(f, "InvalidStateId: Unable to parse state id!")
+            }
+            TableParseError::InvalidTarget => {
+                write!(f, "InvalidTarget: Unable to parse transition target!")
+            }
+            TableParseError::InvalidHeaderSymbol(symbol) => write!(
+                f,
+                "InvalidHeaderSymbol: Unable to decode alphabet symbol {:?}!",
+                symbol
+            ),
+            TableParseError::RowWidthMismatch(state) => write!(
+                f,
+                "RowWidthMismatch: State {} does not have one transition per column!",
+                state
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BuildError {
+    DuplicateState(usize),
+    UnknownSymbol(usize, char),
+    /// The same symbol leads to two different states from one row
+    ConflictingTransition(usize, char),
+    UndefinedTarget(usize, usize),
+    /// The row has more transitions than there are alphabet symbols
+    TooManyColumns(usize),
+}
+impl Error for BuildError {}
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::DuplicateState(id) => {
+                write!(f, "DuplicateState: State {} is defined twice!", id)
+            }
+            BuildError::UnknownSymbol(id, c) => {
+                write!(
+                    f,
+                    "UnknownSymbol: State {} uses {:?}, which is not in the alphabet!",
+                    id, c
+                )
+            }
+            BuildError::ConflictingTransition(id, c) => write!(
+                f,
+                "ConflictingTransition: State {} has several targets on {:?}!",
+                id, c
+            ),
+            BuildError::UndefinedTarget(id, target) => write!(
+                f,
+                "UndefinedTarget: State {} transitions to undefined state {}!",
+                id, target
+            ),
+            BuildError::TooManyColumns(id) => write!(
+                f,
+                "TooManyColumns: State {} has more transitions than alphabet symbols!",
+                id
+            ),
+        }
+    }
+}
+
+type StateDefinition = (usize, bool, Vec<(char, usize)>);
+
+/// Builds a [`LabeledTable`] from in-memory data, with columns in alphabet order
+pub struct TableBuilder {
+    alphabet: Vec<char>,
+    states: Vec<StateDefinition>,
+}
+
+impl TableBuilder {
+    pub fn new(alphabet: impl IntoIterator<Item = char>) -> Self {
+        Self {
+            alphabet: alphabet.into_iter().collect(),
+            states: Vec::new(),
+        }
+    }
+
+    pub fn state(
+        mut self,
+        id: usize,
+        accepting: bool,
+        transitions: impl IntoIterator<Item = (char, usize)>,
+    ) -> Self {
+        self.states
+            .push((id, accepting, transitions.into_iter().collect()));
+        self
+    }
+
+    pub fn build(self) -> Result<LabeledTable, BuildError> {
+        let mut ids = BTreeSet::new();
+        for (id, _, _) in &self.states {
+            if !ids.insert(*id) {
+                return Err(BuildError::DuplicateState(*id));
+            }
+        }
+
+        let alphabet = self.alphabet;
+        let rows = self
+            .states
+            .into_iter()
+            .map(|(id, accepting, edges)| {
+                let mut transitions = vec![None; alphabet.len()];
+                for (c, target) in edges {
+                    let column = alphabet
+                        .iter()
+                        .position(|a| *a == c)
+                        .ok_or(BuildError::UnknownSymbol(id, c))?;
+                    if !ids.contains(&target) {
+                        return Err(BuildError::UndefinedTarget(id, target));
+                    }
+                    match transitions[column] {
+                        Some(existing) if existing != target => {
+                            return Err(BuildError::ConflictingTransition(id, c));
+                        }
+                        _ => transitions[column] = Some(target),
+                    }
+                }
+                Ok(TransitionTableRow {
+                    id,
+                    accepting,
+                    transitions,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        LabeledTable::new(alphabet, TransitionTable { rows })
+    }
+}
@@ -0,0 +1,305 @@
+//! Deterministic single tape Turing machines: definition format, simulation and rendering
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{
+    Digraph, Transition,
+    digraph::escaped,
+    import::{DefinitionError, MachineDefinition, labels},
+    nfa::{State, named_symbol},
+};
+
+#[derive(Debug)]
+pub enum TmError {
+    /// Line number of a row without `<marker> <from> <to> <read> <write> <L|R|S>`
+    ColumnMismatch(usize),
+    InvalidSymbol(String),
+    InvalidMove(String),
+    /// Two rows for the same state and symbol
+    Nondeterministic(String, char),
+    NoTransitions,
+    UnknownStart(String),
+}
+impl Error for TmError {}
+impl From<DefinitionError> for TmError {
+    fn from(value: DefinitionError) -> Self {
+        match value {
+            DefinitionError::ColumnMismatch(line) => TmError::ColumnMismatch(line),
+            DefinitionError::NoTransitions => TmError::NoTransitions,
+            DefinitionError::UnknownStart(start) => TmError::UnknownStart(start),
+        }
+    }
+}
+impl Display for TmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TmError::ColumnMismatch(line) => write!(
+                f,
+                "ColumnMismatch: Line {} is not <marker> <from> <to> <read> <write> <L|R|S>!",
+                line
+            ),
+            TmError::InvalidSymbol(s) => {
+                write!(f, "InvalidSymbol: {:?} is not a single symbol!", s)
+            }
+            TmError::InvalidMove(s) => write!(f, "InvalidMove: {:?} is not L, R or S!", s),
+            TmError::Nondeterministic(state, c) => write!(
+                f,
+                "Nondeterministic: State {} has several rows for {:?}!",
+                state, c
+            ),
+            TmError::NoTransitions => write!(f, "NoTransitions: Definition has no rows!"),
+            TmError::UnknownStart(s) => write!(
+                f,
+                "UnknownStart: The start: directive names no state {:?}!",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Move {
+    Left,
+    Right,
+    Stay,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmTransition {
+    pub to: State,
+    pub write: char,
+    pub head: Move,
+}
+
+/// A Turing machine that accepts by entering an accepting state and rejects by halting, when no
+/// row matches the state and the symbol under the head, anywhere else
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TM {
+    states: BTreeMap<State, bool>,
+    transitions: BTreeMap<(State, char), TmTransition>,
+    blank: char,
+    labels: BTreeMap<State, String>,
+}
+
+/// The machine between two moves, `tape` trimmed of blanks around the input and the head
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub state: State,
+    pub tape: Vec<char>,
+    /// Index of the head in `tape`
+    pub head: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Halt {
+    Accepted,
+    Rejected,
+    /// Still running after the allowed number of moves
+    StepLimit,
+}
+
+pub struct Run {
+    /// The initial configuration and one snapshot after every move
+    pub steps: Vec<Snapshot>,
+    pub halt: Halt,
+}
+
+impl TM {
+    /// Accepting flag of every state
+    pub fn states(&self) -> &BTreeMap<State, bool> {
+        &self.states
+    }
+
+    /// The move for every state and symbol under the head
+    pub fn transitions(&self) -> &BTreeMap<(State, char), TmTransition> {
+        &self.transitions
+    }
+
+    pub fn blank(&self) -> char {
+        self.blank
+    }
+
+    /// Original names of states defined by name, numeric states have none
+    pub fn labels(&self) -> &BTreeMap<State, String> {
+        &self.labels
+    }
+
+    /// The name a state was defined with, or its id
+    pub fn state_name(&self, id: State) -> String {
+        self.labels
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// The lowest numbered state
+    pub fn start(&self) -> Option<State> {
+        self.states.keys().next().copied()
+    }
+
+    /// Runs on `input` until the machine halts or `max_steps` moves were made
+    pub fn run(&self, input: &str, max_steps: usize) -> Run {
+        let Some(mut state) = self.start() else {
+            return Run {
+                steps: Vec::new(),
+                halt: Halt::Rejected,
+            };
+        };
+        let mut tape: VecDeque<char> = input.chars().collect();
+        let mut head = 0;
+        if tape.is_empty() {
+            tape.push_back(self.blank);
+        }
+
+        let mut steps = vec![self.snapshot(state, &tape, head)];
+        let halt = loop {
+            if self.states.get(&state) == Some(&true) {
+                break Halt::Accepted;
+            }
+            let Some(t) = self.transitions.get(&(state, tape[head])) else {
+                break Halt::Rejected;
+            };
+            if steps.len() > max_steps {
+                break Halt::StepLimit;
+            }
+            tape[head] = t.write;
+            state = t.to;
+            match t.head {
+                Move::Left if head == 0 => tape.push_front(self.blank),
+                Move::Left => head -= 1,
+                Move::Right => {
+                    head += 1;
+                    if head == tape.len() {
+                        tape.push_back(self.blank);
+                    }
+                }
+                Move::Stay => {}
+            }
+            steps.push(self.snapshot(state, &tape, head));
+        };
+        Run { steps, halt }
+    }
+
+    fn snapshot(&self, state: State, tape: &VecDeque<char>, head: usize) -> Snapshot {
+        let used = |i: &usize| tape[*i] != self.blank || *i == head;
+        let first = (0..tape.len()).find(used).unwrap_or(head);
+        let last = (0..tape.len()).rfind(used).unwrap_or(head);
+        Snapshot {
+            state,
+            tape: tape.range(first..=last).copied().collect(),
+            head: head - first,
+        }
+    }
+
+    /// Whether the machine accepts `input` within `max_steps` moves
+    pub fn accepts(&self, input: &str, max_steps: usize) -> bool {
+        self.run(input, max_steps).halt == Halt::Accepted
+    }
+
+    /// `state: tape` with the symbol under the head in brackets
+    pub fn describe(&self, snapshot: &Snapshot) -> String {
+        let tape: String = snapshot
+            .tape
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match i == snapshot.head {
+                true => format!("[{}]", c),
+                false => c.to_string(),
+            })
+            .collect();
+        format!("{}: {}", self.state_name(snapshot.state), tape)
+    }
+}
+
+fn symbol(token: &str) -> Result<char, TmError> {
+    let mut chars = token.chars();
+    match (named_symbol(token), chars.next(), chars.next()) {
+        (Some(c), _, _) | (None, Some(c), None) => Ok(c),
+        _ => Err(TmError::InvalidSymbol(token.to_owned())),
+    }
+}
+
+impl FromStr for TM {
+    type Err = TmError;
+
+    /// Rows are `<+|-> <from> <to> <read> <write> <L|R|S>`, a `+` marking `from` as accepting.
+    /// Leading `start: <state>` and `blank: <symbol>` (default `_`) directives may come first.
+    /// The start state is the first row's `from` unless given. An accepting state needs no
+    /// moves of its own since the machine stops there, a row like `+ qa qa _ _ S` declares it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let definition = MachineDefinition::parse(s, &["start", "blank"])?;
+        let blank = symbol(definition.directives.get("blank").copied().unwrap_or("_"))?;
+        let ids = definition.ids();
+
+        let mut states: BTreeMap<State, bool> = ids.values().map(|id| (*id, false)).collect();
+        let mut transitions = BTreeMap::new();
+        for row in &definition.rows {
+            let [read, write, head] = row.fields;
+            let id = ids[&row.from.to_string()];
+            *states.entry(id).or_default() |= row.accepting;
+            let read = symbol(read)?;
+            let transition = TmTransition {
+                to: ids[&row.to.to_string()],
+                write: symbol(write)?,
+                head: match head {
+                    "L" => Move::Left,
+                    "R" => Move::Right,
+                    "S" => Move::Stay,
+                    _ => return Err(TmError::InvalidMove(head.to_owned())),
+                },
+            };
+            if transitions.insert((id, read), transition).is_some() {
+                return Err(TmError::Nondeterministic(row.from.to_owned(), read));
+            }
+        }
+
+        Ok(TM {
+            states,
+            transitions,
+            blank,
+            labels: labels(&ids),
+        })
+    }
+}
+
+/// Edges are labeled `read/write,move`, several transitions between the same states one per line
+impl From<&TM> for Digraph {
+    fn from(value: &TM) -> Self {
+        let mut graph = Digraph {
+            start: value.start(),
+            labels: escaped(&value.labels),
+            accepting_nodes: value
+                .states
+                .iter()
+                .filter(|(_, accepting)| **accepting)
+                .map(|(s, _)| *s)
+                .collect(),
+            ..Default::default()
+        };
+        for ((from, read), t) in &value.transitions {
+            let head = match t.head {
+                Move::Left => "L",
+                Move::Right => "R",
+                Move::Stay => "S",
+            };
+            let label = format!(
+                "{}/{},{}",
+                Transition::Char(*read),
+                Transition::Char(t.write),
+                head
+            );
+            graph
+                .edges
+                .entry((*from, BTreeSet::from([t.to])))
+                .and_modify(|acc| {
+                    acc.push_str("<br/>");
+                    acc.push_str(&label);
+                })
+                .or_insert(label);
+        }
+        graph
+    }
+}
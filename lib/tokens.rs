@@ -0,0 +1,56 @@
+//! Symbols of more than one character, like `:=` or `while`, for automata that run over the
+//! tokens of a prior lexer. Each token is interned as a character from the private use area,
+//! which every algorithm treats like any other symbol, and its name is kept next to the alphabet
+//! to write it back out.
+
+use std::collections::BTreeMap;
+
+use crate::{LabelStyle, Transition, digraph::escape};
+
+/// Token names keyed by the character standing for them
+pub(crate) type Tokens = BTreeMap<char, String>;
+
+/// The Unicode private use area
+const PRIVATE_USE: std::ops::RangeInclusive<u32> = 0xE000..=0xF8FF;
+
+/// The character standing for `token`, a single character stands for itself. A longer token is
+/// given the next free private use character unless it is interned already, `None` for an empty
+/// token or once the private use area is used up.
+pub(crate) fn intern(tokens: &mut Tokens, token: &str) -> Option<char> {
+    if let Some(c) = symbol(tokens, token) {
+        return Some(c);
+    }
+    let next = tokens
+        .keys()
+        .next_back()
+        .map_or(*PRIVATE_USE.start(), |c| *c as u32 + 1);
+    let c = char::from_u32(next).filter(|c| PRIVATE_USE.contains(&(*c as u32)))?;
+    tokens.insert(c, token.to_owned());
+    Some(c)
+}
+
+/// The character `token` is written as, `None` if it is neither a single character nor interned
+pub(crate) fn symbol(tokens: &Tokens, token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        (Some(_), Some(_)) => tokens.iter().find(|(_, t)| *t == token).map(|(c, _)| *c),
+        (None, _) => None,
+    }
+}
+
+/// The input standing for the token stream `input`, see [`symbol`]
+pub(crate) fn spell(tokens: &Tokens, input: &[&str]) -> Option<String> {
+    input.iter().map(|t| symbol(tokens, t)).collect()
+}
+
+/// [`Transition::render`] with interned tokens written by name
+pub(crate) fn render(tokens: &Tokens, transition: &Transition, style: &LabelStyle) -> String {
+    match transition {
+        Transition::Char(c) if let Some(token) = tokens.get(c) => match style.html {
+            true => escape(token),
+            false => token.clone(),
+        },
+        t => t.render(style),
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::{LabeledTable, NFA, Transition};
+
+#[derive(Debug)]
+pub struct InvariantViolation(pub String);
+impl Error for InvariantViolation {}
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InvariantViolation: {}", self.0)
+    }
+}
+
+/// Structural invariants every transformation must preserve
+pub trait Validate {
+    fn validate_invariants(&self) -> Result<(), InvariantViolation>;
+}
+
+fn violation(message: String) -> Result<(), InvariantViolation> {
+    Err(InvariantViolation(message))
+}
+
+fn unique_alphabet(alphabet: &[char]) -> Result<(), InvariantViolation> {
+    let mut seen = BTreeSet::new();
+    match alphabet.iter().find(|c| !seen.insert(**c)) {
+        Some(c) => violation(format!("alphabet contains {:?} twice", c)),
+        None => Ok(()),
+    }
+}
+
+impl Validate for NFA {
+    fn validate_invariants(&self) -> Result<(), InvariantViolation> {
+        unique_alphabet(self.alphabet())?;
+        for (state, (_, transitions)) in self.states() {
+            for (t, targets) in transitions {
+                if let Transition::Char(c) = t
+                    && !self.alphabet().contains(&c)
+                {
+                    return violation(format!("state {} uses {:?} outside the alphabet", state, c));
+                }
+                if targets.is_empty() {
+                    return violation(format!("state {} has an empty target set on {}", state, t));
+                }
+            }
+        }
+        if let Some(state) = self
+            .labels()
+            .keys()
+            .find(|s| !self.states().contains_key(s))
+        {
+            return violation(format!("label of missing state {}", state));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for LabeledTable {
+    fn validate_invariants(&self) -> Result<(), InvariantViolation> {
+        unique_alphabet(self.alphabet())?;
+        let mut ids = BTreeSet::new();
+        for row in &self.table().rows {
+            if !ids.insert(row.id) {
+                return violation(format!("state {} has more than one row", row.id));
+            }
+            if row.transitions.len() > self.alphabet().len() {
+                return violation(format!(
+                    "state {} has {} columns for {} symbols",
+                    row.id,
+                    row.transitions.len(),
+                    self.alphabet().len()
+                ));
+            }
+        }
+        for row in &self.table().rows {
+            if let Some(target) = row.transitions.iter().flatten().find(|t| !ids.contains(t)) {
+                return violation(format!(
+                    "state {} transitions to missing state {}",
+                    row.id, target
+                ));
+            }
+        }
+        if let Some(state) = self.state_labels.keys().find(|s| !ids.contains(s)) {
+            return violation(format!("label of missing state {}", state));
+        }
+        Ok(())
+    }
+}
+
+/// Panics with the violated invariant in debug builds or with the `validate-invariants` feature
+pub(crate) fn debug_validate<T: Validate>(value: &T, after: &str) {
+    if cfg!(any(debug_assertions, feature = "validate-invariants"))
+        && let Err(e) = value.validate_invariants()
+    {
+        panic!("{} after {}", e, after);
+    }
+}
@@ -0,0 +1,142 @@
+//! JavaScript bindings, enabled with the `wasm` feature. Every function takes the text of a
+//! definition in any format [`crate::frontend::detect`] recognizes. The `wasm` crate in this
+//! workspace packages them, `wasm-pack build wasm`.
+
+use std::collections::BTreeSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Automaton, Digraph, NFA, SerializeOptions, Transition, frontend};
+
+fn load(input: &str) -> Result<Automaton, JsError> {
+    let frontend =
+        frontend::detect(input).ok_or_else(|| JsError::new("Unable to detect input format"))?;
+    frontend
+        .parse(input)
+        .map_err(|e| JsError::new(&format!("{:#}", e)))
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Graphviz DOT for the automaton, optionally minimized first
+#[wasm_bindgen]
+pub fn render(input: &str, minimize: bool, show_useless: bool) -> Result<String, JsError> {
+    let mut automaton = load(input)?;
+    if minimize {
+        automaton = Automaton::Table(automaton.to_nfa().minimize_brzozowski());
+    }
+    let graph = Digraph::from(&automaton);
+    Ok(match show_useless {
+        true => graph.mark_useless(),
+        false => graph,
+    }
+    .to_string())
+}
+
+/// The equivalent DFA in the transition table format
+#[wasm_bindgen]
+pub fn determinize(input: &str) -> Result<String, JsError> {
+    let table = match load(input)? {
+        Automaton::Table(tt) => tt,
+        Automaton::Nfa(nfa) => nfa.determinize(),
+    };
+    Ok(table.serialize(&SerializeOptions::default()))
+}
+
+/// The minimal DFA in the transition table format
+#[wasm_bindgen]
+pub fn minimize(input: &str) -> Result<String, JsError> {
+    Ok(load(input)?
+        .to_nfa()
+        .minimize_brzozowski()
+        .serialize(&SerializeOptions::default()))
+}
+
+/// `{"start", "alphabet", "states": [{"id", "accepting"}], "transitions": [{"from", "to", "symbol"}]}`,
+/// lambda transitions have a `null` symbol
+#[wasm_bindgen]
+pub fn to_json(input: &str) -> Result<String, JsError> {
+    let nfa = load(input)?.to_nfa();
+    let states = nfa.states();
+    let tokens = nfa.tokens();
+    let alphabet: Vec<String> = nfa
+        .alphabet()
+        .iter()
+        .map(|c| json_string(tokens.get(c).unwrap_or(&c.to_string())))
+        .collect();
+    let state_objects: Vec<String> = states
+        .iter()
+        .map(|(id, (accepting, _))| format!("{{\"id\":{},\"accepting\":{}}}", id, accepting))
+        .collect();
+    let transitions: Vec<String> = states
+        .iter()
+        .flat_map(|(from, (_, transitions))| {
+            transitions.iter().flat_map(move |(t, targets)| {
+                let symbol = match t {
+                    Transition::Char(c) => json_string(tokens.get(c).unwrap_or(&c.to_string())),
+                    Transition::Lambda => "null".to_owned(),
+                };
+                targets.iter().map(move |to| {
+                    format!("{{\"from\":{},\"to\":{},\"symbol\":{}}}", from, to, symbol)
+                })
+            })
+        })
+        .collect();
+    Ok(format!(
+        "{{\"start\":{},\"alphabet\":[{}],\"states\":[{}],\"transitions\":[{}]}}",
+        nfa.start().map_or("null".to_owned(), |s| s.to_string()),
+        alphabet.join(","),
+        state_objects.join(","),
+        transitions.join(",")
+    ))
+}
+
+/// Runs an automaton one symbol at a time so a page can highlight the active states
+#[wasm_bindgen]
+pub struct Simulation {
+    nfa: NFA,
+    current: BTreeSet<usize>,
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: &str) -> Result<Simulation, JsError> {
+        let nfa = load(input)?.to_nfa();
+        let current = nfa.lambda_closure(&nfa.start().into_iter().collect());
+        Ok(Self { nfa, current })
+    }
+
+    /// Consumes one symbol, returns whether any state is still active
+    pub fn step(&mut self, symbol: char) -> bool {
+        self.current = self.nfa.step(&self.current, symbol);
+        !self.current.is_empty()
+    }
+
+    pub fn active_states(&self) -> Vec<usize> {
+        self.current.iter().copied().collect()
+    }
+
+    pub fn is_accepting(&self) -> bool {
+        self.current.iter().any(|s| self.nfa.is_accepting(*s))
+    }
+
+    /// Back to the start state, as before the first step
+    pub fn reset(&mut self) {
+        self.current = self
+            .nfa
+            .lambda_closure(&self.nfa.start().into_iter().collect());
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::{Parser, Subcommand};
 
@@ -6,11 +7,229 @@ use clap::{Parser, Subcommand};
 pub struct CliArgs {
     #[command(subcommand)]
     pub mode: Mode,
+    /// Input file, `-` reads standard input
     pub file: PathBuf,
+    /// Write results to this file instead of standard output
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// Input format (nfa, table, mermaid, jff, dot), overriding the one implied by the mode
+    #[arg(long)]
+    pub format: Option<String>,
+    /// Gray out states that are unreachable or cannot reach an accepting state
+    #[arg(long)]
+    pub show_useless: bool,
+    /// Name states by the shortest string reaching them
+    #[arg(long)]
+    pub name_states: bool,
+    /// Replace lambda transitions with equivalent direct transitions before anything else
+    #[arg(long)]
+    pub remove_lambdas: bool,
+    /// Shrink the NFA by dropping useless states and merging bisimilar ones, after removing
+    /// lambdas
+    #[arg(long)]
+    pub optimize: bool,
+    /// Replace the automaton with its minimal DFA
+    #[arg(long)]
+    pub minimize: bool,
+    /// How --minimize finds the minimal DFA: brzozowski (the default) or partition refinement
+    #[arg(long, value_parser = ["brzozowski", "partition"], requires = "minimize")]
+    pub minimizer: Option<String>,
+    /// Send missing transitions of a deterministic automaton to an explicit trap state
+    #[arg(long)]
+    pub complete: bool,
+    /// Spell lambda this way in graph labels instead of &lambda;, e.g. ε or &epsilon;
+    #[arg(long)]
+    pub lambda: Option<String>,
+    /// Give up on determinizing or minimizing once it needs more than this many DFA states
+    #[arg(long)]
+    pub max_states: Option<usize>,
+    /// Give up on determinizing or minimizing after this many seconds
+    #[arg(long, value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+    /// Skip rows of an NFA definition that fail to parse, printing them as warnings, instead of
+    /// rejecting the whole file
+    #[arg(long)]
+    pub lenient: bool,
+    /// Leave the trap state added by --complete out of the graph
+    #[arg(long, requires = "complete")]
+    pub hide_trap: bool,
+}
+
+/// Seconds as a [`Duration`], rejecting negative, NaN and out of range values
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{}", e))
 }
 
 #[derive(Subcommand)]
 pub enum Mode {
     NFA,
     DFA,
+    /// Detect the input format and render it
+    Show,
+    /// Print the shortest accepted strings, determinizing first if needed
+    Examples {
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+        #[arg(long, default_value_t = 16)]
+        max_len: usize,
+    },
+    /// Print random accepted strings one per line, quoted like `examples` so the empty string and
+    /// whitespace show, determinizing first if needed. The first ones together take every
+    /// transition that can be taken in a short enough string.
+    Gen {
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+        #[arg(long, default_value_t = 16)]
+        max_len: usize,
+        /// Seed for a reproducible corpus
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Print near misses instead: rejected strings one inserted, deleted or replaced symbol
+        /// away from an accepted one
+        #[arg(long)]
+        rejected: bool,
+    },
+    /// Print the shortest accepted and shortest rejected strings
+    Shortest,
+    /// Print the accepted string fewest inserted, deleted or replaced symbols away from INPUT
+    /// and that number, determinizing first if needed
+    Closest {
+        input: String,
+    },
+    /// Print emptiness, finiteness and word counts of the language
+    Analyze {
+        /// Count accepted strings of every length up to this one
+        #[arg(long, default_value_t = 8)]
+        max_len: usize,
+    },
+    /// Print size and structure statistics
+    Stats,
+    /// Print the automaton as a transition table, determinizing it if needed
+    Table {
+        /// Written for missing transitions
+        #[arg(long, default_value = "E")]
+        dead: String,
+        /// Pad columns to equal width
+        #[arg(long)]
+        align: bool,
+        /// Start with a line listing the alphabet, marked with `#`
+        #[arg(long)]
+        header: bool,
+        /// Mark accepting rows with 1/0 instead of +/-
+        #[arg(long)]
+        binary: bool,
+        /// Draw an aligned box table for reading instead of the parseable format
+        #[arg(long, conflicts_with = "output_format")]
+        pretty: bool,
+        /// Write a Markdown (md) or CSV (csv) table for reports and spreadsheets instead of the
+        /// parseable format
+        #[arg(long, value_parser = ["md", "csv"])]
+        output_format: Option<String>,
+    },
+    /// Reprint the input in canonical form: an NFA definition stays one, with rows sorted and
+    /// duplicate rows merged, and a table gets an alphabet header and rows sorted by state
+    Fmt,
+    /// Print the subset construction step by step: every DFA state found, the NFA states it was
+    /// reached as, their lambda closure and the closure reached on every symbol
+    Worksheet {
+        /// Write a Markdown (md) or CSV (csv) table instead of a box-drawn one
+        #[arg(long, value_parser = ["md", "csv"])]
+        output_format: Option<String>,
+    },
+    /// Draw the subset construction over the NFA: every DFA state as a box around the NFA
+    /// states it is made of, with the DFA transitions between the boxes
+    Overlay,
+    /// Compare FILE against OTHER, determinizing both: equivalence, a distinguishing string,
+    /// unmatched states and the transitions that differ
+    Diff {
+        other: PathBuf,
+        /// Print the product graph with the differences colored instead
+        #[arg(long)]
+        dot: bool,
+    },
+    /// Draw FILE and every one of OTHERS in one graph, each boxed in and titled with its file name
+    Combine {
+        #[arg(required = true)]
+        others: Vec<PathBuf>,
+    },
+    /// Group the states into Myhill–Nerode classes, determinizing first if needed, with the
+    /// shortest string reaching every class and the shortest string telling any two apart
+    Nerode {
+        /// Print the graph with the states of every class boxed in together instead
+        #[arg(long, conflicts_with_all = ["html", "trace"])]
+        dot: bool,
+        /// Write an HTML page with the classes, distinguishing strings, refinement rounds and
+        /// graph instead
+        #[arg(long, conflicts_with = "trace")]
+        html: bool,
+        /// Print the partition after every round of refinement and the block and symbol that
+        /// split it instead
+        #[arg(long)]
+        trace: bool,
+    },
+    /// Print every line of INPUT containing a string the automaton accepts, as
+    /// `file:line:column: text` with the match highlighted on a terminal
+    Grep {
+        #[arg(required = true)]
+        input: Vec<PathBuf>,
+        /// Also report matches starting inside an earlier match
+        #[arg(long)]
+        overlapping: bool,
+        /// Scan the raw bytes of INPUT, symbols up to U+00FF standing for the byte of the same
+        /// value, and print `file:offset: match` with the byte offset counted from 0
+        #[arg(long, conflicts_with = "overlapping")]
+        bytes: bool,
+    },
+    /// Read FILE as a right-linear grammar and print the equivalent NFA definition
+    Grammar2nfa,
+    /// Print a right-linear grammar generating the automaton's language
+    Nfa2grammar,
+    /// Step through INPUT one symbol at a time in an interactive view of the transition table,
+    /// determinizing first if needed
+    #[cfg(feature = "tui")]
+    Tui {
+        #[arg(default_value = "")]
+        input: String,
+    },
+    /// Print the automaton as a JFLAP .jff file
+    Jff,
+    /// Print a standalone Rust recognizer for the automaton, determinizing it if needed
+    Codegen {
+        /// Prefix of the generated functions and name of the token variant
+        #[arg(long, default_value = "automaton")]
+        name: String,
+        /// Write a C scanner to NAME.h and NAME.c in the --out directory instead
+        #[arg(long)]
+        c: bool,
+    },
+    /// Write an HTML page with the table, graph, statistics and example strings
+    Report {
+        /// Number of accepted strings to list
+        #[arg(short = 'n', long, default_value_t = 10)]
+        examples: usize,
+    },
+    /// Treat FILE as a pushdown automaton and render it, see `fa_viz::pda`
+    Pda {
+        /// Print the configurations reached on this input and whether it is accepted instead
+        #[arg(long)]
+        run: Option<String>,
+        /// Give up on a run after this many moves
+        #[arg(long, default_value_t = 1000)]
+        max_steps: usize,
+    },
+    /// Treat FILE as a Turing machine and render it, see `fa_viz::tm`
+    Tm {
+        /// Print the tape after every move on this input and how the machine halted instead
+        #[arg(long)]
+        run: Option<String>,
+        /// Give up on a run after this many moves
+        #[arg(long, default_value_t = 1000)]
+        max_steps: usize,
+        /// Write the run as an HTML page instead of text
+        #[arg(long, requires = "run")]
+        html: bool,
+    },
+    /// Render every file in the FILE directory to a .dot file in the --out directory
+    Batch,
 }
@@ -1,42 +1,586 @@
 mod cli_args;
-use std::{path::Path, str::FromStr};
+#[cfg(feature = "tui")]
+mod tui;
+use std::fs::File;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use cli_args::CliArgs;
-use transition_tables::TransitionTable;
+use cli_args::{CliArgs, Mode};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::{RngCore, SeedableRng, rngs::StdRng};
 
+use fa_viz::frontend::{self, NfaFrontend, TableFrontend};
 use fa_viz::*;
 
 fn main() -> Result<()> {
     let args = CliArgs::parse();
+    if let Mode::Batch = args.mode {
+        let out = args
+            .out
+            .as_deref()
+            .context("Batch mode needs an --out directory")?;
+        return batch(&args, out);
+    }
+    if let Mode::Pda { ref run, max_steps } = args.mode {
+        let pda = pda::PDA::from_str(&read_file(&args.file)?)
+            .context("Unable to parse input file to PDA")?;
+        let mut out = output(&args)?;
+        return match run {
+            Some(input) => trace_pda(&mut out, &pda, input, max_steps),
+            None => Ok(writeln!(out, "{}", Digraph::from(&pda))?),
+        };
+    }
+    if let Mode::Tm {
+        ref run,
+        max_steps,
+        html,
+    } = args.mode
+    {
+        let tm = tm::TM::from_str(&read_file(&args.file)?)
+            .context("Unable to parse input file to TM")?;
+        let mut out = output(&args)?;
+        let Some(input) = run else {
+            return Ok(writeln!(out, "{}", Digraph::from(&tm))?);
+        };
+        let run = tm.run(input, max_steps);
+        if html {
+            let title = args.file.display().to_string();
+            return Ok(write!(out, "{}", report::tape_html(&title, &tm, &run))?);
+        }
+        for (step, snapshot) in run.steps.iter().enumerate() {
+            writeln!(out, "{}: {}", step, tm.describe(snapshot))?;
+        }
+        let halt = match run.halt {
+            tm::Halt::Accepted => "accepted",
+            tm::Halt::Rejected => "rejected",
+            tm::Halt::StepLimit => "step limit",
+        };
+        return Ok(writeln!(out, "halted: {}", halt)?);
+    }
+    if let Mode::Grammar2nfa = args.mode {
+        let grammar = grammar::Grammar::from_str(&read_file(&args.file)?)
+            .context("Unable to parse input file to grammar")?;
+        return Ok(write!(
+            output(&args)?,
+            "{}",
+            NFA::from(&grammar).serialize()
+        )?);
+    }
+    if let Mode::Codegen { ref name, c: true } = args.mode {
+        let (automaton, _) = load(&args, &read_file(&args.file)?)?;
+        let dir = args.out.as_deref().unwrap_or(Path::new("."));
+        let header_name = format!("{}.h", name);
+        let sources = codegen::c(&[(name, &to_table(&args, automaton)?)], &header_name)?;
+        for (file, contents) in [
+            (header_name.clone(), sources.header),
+            (format!("{}.c", name), sources.source),
+        ] {
+            let path = dir.join(file);
+            std::fs::write(&path, contents)
+                .with_context(|| format!("Unable to write {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    let input = read_file(&args.file)?;
+    let (automaton, trap) = load(&args, &input)?;
+    let mut out = output(&args)?;
 
     match args.mode {
-        cli_args::Mode::NFA => {
-            let nfa: NFA = NFA::from_str(&read_file(&args.file))
-                .context("Unable to parse input file to NFA")?;
-            let graph = Digraph::from(&nfa);
-            println!("{}", graph);
+        Mode::Examples { count, max_len } => {
+            let nfa = NFA::from(&to_table(&args, automaton)?);
+            for word in nfa.enumerate_accepted(max_len).take(count) {
+                writeln!(out, "{:?}", word)?;
+            }
+        }
+        Mode::Gen {
+            count,
+            max_len,
+            seed,
+            rejected,
+        } => {
+            let table = to_table(&args, automaton)?;
+            let mut rng: Box<dyn RngCore> = match seed {
+                Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
+                None => Box::new(rand::thread_rng()),
+            };
+            let words = match rejected {
+                true => table.generate_rejected(count, max_len, &mut rng),
+                false => table.generate_accepted(count, max_len, &mut rng),
+            };
+            for word in words {
+                writeln!(out, "{:?}", word)?;
+            }
+        }
+        Mode::Closest { ref input } => {
+            let table = to_table(&args, automaton)?;
+            match table.closest_accepted(input) {
+                Some((word, distance)) => {
+                    writeln!(out, "closest: {:?}", word)?;
+                    writeln!(out, "distance: {}", distance)?;
+                }
+                None => writeln!(out, "closest: none")?,
+            }
+        }
+        Mode::Shortest => {
+            let nfa = NFA::from(&to_table(&args, automaton)?);
+            let show = |w: Option<String>| w.map_or("none".to_owned(), |w| format!("{:?}", w));
+            writeln!(out, "accepted: {}", show(nfa.shortest_accepted()))?;
+            writeln!(out, "rejected: {}", show(nfa.shortest_rejected()))?;
+        }
+        Mode::Analyze { max_len } => {
+            // Determinized within the limits first, every analysis of a DFA is cheap
+            let nfa = NFA::from(&to_table(&args, automaton)?);
+            writeln!(out, "empty: {}", nfa.is_empty())?;
+            writeln!(out, "finite: {}", nfa.is_finite())?;
+            for n in 0..=max_len {
+                writeln!(
+                    out,
+                    "words of length {}: {}",
+                    n,
+                    nfa.count_words_of_length(n)
+                )?;
+            }
+        }
+        Mode::Stats => writeln!(out, "{}", Stats::from(&automaton))?,
+        Mode::Table {
+            ref dead,
+            align,
+            header,
+            binary,
+            pretty,
+            ref output_format,
+        } => {
+            let options = SerializeOptions {
+                dead_symbol: dead.clone(),
+                align,
+                header,
+                accepting_markers: match binary {
+                    true => AcceptingMarkers::Binary,
+                    false => AcceptingMarkers::PlusMinus,
+                },
+            };
+            let table = to_table(&args, automaton)?;
+            match (pretty, output_format) {
+                (true, _) => write!(out, "{}", table.pretty_print())?,
+                (_, Some(format)) => write!(out, "{}", table.render(table_format(Some(format))))?,
+                _ => write!(out, "{}", table.serialize(&options))?,
+            }
+        }
+        Mode::Codegen { ref name, .. } => {
+            let table = to_table(&args, automaton)?;
+            write!(out, "{}", codegen::rust(&[(name, &table)])?)?;
+        }
+        Mode::Report { examples } => {
+            let title = args.file.display().to_string();
+            let html = with_progress(|progress| {
+                report::try_html_with(&title, &automaton, examples, &limits(&args), progress)
+            })
+            .context("Unable to build report")?;
+            write!(out, "{}", html)?;
+        }
+        Mode::Fmt => match automaton {
+            Automaton::Nfa(nfa) => write!(out, "{}", nfa.serialize())?,
+            Automaton::Table(mut tt) => {
+                tt.sort_rows();
+                let options = SerializeOptions {
+                    header: true,
+                    ..Default::default()
+                };
+                write!(out, "{}", tt.serialize(&options))?;
+            }
+        },
+        Mode::Worksheet { ref output_format } => {
+            let format = table_format(output_format.as_deref());
+            let nfa = automaton.to_nfa();
+            let worksheet =
+                with_progress(|progress| nfa.try_worksheet_with(&limits(&args), progress))
+                    .context("Unable to determinize")?;
+            write!(out, "{}", worksheet.render(format))?
+        }
+        Mode::Overlay => {
+            let nfa = automaton.to_nfa();
+            let overlay =
+                with_progress(|progress| nfa.try_subset_overlay_with(&limits(&args), progress))
+                    .context("Unable to determinize")?;
+            writeln!(out, "{}", overlay)?
+        }
+        Mode::Diff { ref other, dot } => {
+            let (second, _) = load(&args, &read_file(other)?)?;
+            let diff = diff::diff(&to_table(&args, automaton)?, &to_table(&args, second)?);
+            match dot {
+                true => writeln!(out, "{}", diff.to_digraph())?,
+                false => writeln!(out, "{}", diff)?,
+            }
+        }
+        Mode::Combine { ref others } => {
+            let mut graphs = vec![(
+                args.file.display().to_string(),
+                render(&args, &automaton, trap),
+            )];
+            for other in others {
+                let (automaton, trap) = load(&args, &read_file(other)?)?;
+                graphs.push((other.display().to_string(), render(&args, &automaton, trap)));
+            }
+            writeln!(out, "{}", Digraph::combined(&graphs))?
+        }
+        Mode::Nerode { dot, html, trace } => {
+            let table = to_table(&args, automaton)?;
+            let classes = nerode::classes(&table);
+            let steps = nerode::refinement(&table);
+            match (dot, html, trace) {
+                (true, _, _) => writeln!(out, "{}", classes.to_digraph(&table))?,
+                (_, true, _) => {
+                    let title = args.file.display().to_string();
+                    let html = report::nerode_html(&title, &table, &classes, &steps);
+                    write!(out, "{}", html)?
+                }
+                (_, _, true) => {
+                    for (round, step) in steps.iter().enumerate() {
+                        writeln!(out, "{}", describe_step(&table, round, step))?;
+                    }
+                }
+                _ => write!(out, "{}", classes)?,
+            }
+        }
+        Mode::Grep {
+            ref input,
+            bytes: true,
+            ..
+        } => {
+            let dfa = bytes::ByteDfa::new(&to_table(&args, automaton)?)
+                .context("Byte mode needs an alphabet of bytes")?;
+            grep_bytes(&mut out, &dfa, input)?;
         }
-        cli_args::Mode::DFA => {
-            let tt = TransitionTable::parse(&read_file(&args.file))
-                .context("Unable to parse input file to DFA")?;
-            let graph = Digraph::from(&tt);
-            println!("{}", graph);
+        Mode::Grep {
+            ref input,
+            overlapping,
+            ..
+        } => {
+            let highlight = args.out.is_none() && std::io::stdout().is_terminal();
+            grep(&mut out, &automaton.to_nfa(), input, overlapping, highlight)?;
+        }
+        Mode::Nfa2grammar => write!(out, "{}", grammar::Grammar::from(&automaton.to_nfa()))?,
+        #[cfg(feature = "tui")]
+        Mode::Tui { ref input } => tui::run(&to_table(&args, automaton)?, input)?,
+        Mode::Jff => write!(out, "{}", jflap::serialize(&automaton.to_nfa()))?,
+        Mode::NFA | Mode::DFA | Mode::Show => writeln!(out, "{}", render(&args, &automaton, trap))?,
+        Mode::Batch | Mode::Grammar2nfa | Mode::Pda { .. } | Mode::Tm { .. } => {
+            unreachable!("returns early")
         }
     }
 
     Ok(())
 }
 
-fn read_file(p: &Path) -> String {
-    use std::fs::File;
-    use std::io::Read;
+/// Parses `input` the way the mode asks and applies the transformation flags.
+/// Also returns the trap state added by `--complete`, if any.
+fn load(args: &CliArgs, input: &str) -> Result<(Automaton, Option<usize>)> {
+    let mut automaton = match (&args.format, &args.mode) {
+        _ if args.lenient => {
+            let format = match (&args.format, &args.mode) {
+                (Some(name), _) => name.as_str(),
+                (None, Mode::NFA) => "nfa",
+                (None, Mode::DFA) => "table",
+                _ => frontend::detect(input).map_or("unknown", |f| f.name()),
+            };
+            anyhow::ensure!(
+                format == "nfa",
+                "--lenient only applies to the nfa format, not {}",
+                format
+            );
+            let (nfa, skipped) =
+                NFA::parse_lenient(input).context("Unable to parse input file to NFA")?;
+            for e in &skipped {
+                eprintln!("Skipped row: {}", e);
+            }
+            Automaton::Nfa(nfa)
+        }
+        (Some(name), _) => {
+            let frontend = frontend::frontend_by_name(name)
+                .with_context(|| format!("Unknown input format {}", name))?;
+            frontend
+                .parse(input)
+                .with_context(|| format!("Unable to parse input file as {}", frontend.name()))?
+        }
+        (None, Mode::NFA) => NfaFrontend
+            .parse(input)
+            .context("Unable to parse input file to NFA")?,
+        (None, Mode::DFA) => TableFrontend
+            .parse(input)
+            .context("Unable to parse input file to DFA")?,
+        _ => {
+            let frontend = frontend::detect(input).context("Unable to detect input format")?;
+            frontend
+                .parse(input)
+                .with_context(|| format!("Unable to parse input file as {}", frontend.name()))?
+        }
+    };
+
+    if args.remove_lambdas {
+        automaton = Automaton::Nfa(automaton.to_nfa().remove_lambdas());
+    }
+
+    if args.optimize {
+        automaton = Automaton::Nfa(automaton.to_nfa().optimize());
+    }
+
+    if args.minimize {
+        let nfa = automaton.to_nfa();
+        let minimizer = match args.minimizer.as_deref() {
+            Some("partition") => Minimizer::PartitionRefinement,
+            _ => Minimizer::Brzozowski,
+        };
+        let minimal =
+            with_progress(|progress| nfa.try_minimize_with(minimizer, &limits(args), progress))
+                .context("Unable to minimize")?;
+        automaton = Automaton::Table(minimal);
+    }
+
+    let mut trap = None;
+    if args.complete {
+        automaton = automaton
+            .convert(AutomatonKind::Table)
+            .context("Completion requires a deterministic automaton")?;
+        if let Automaton::Table(tt) = &mut automaton {
+            trap = tt.complete();
+        }
+    }
+
+    Ok((automaton, trap))
+}
+
+fn render(args: &CliArgs, automaton: &Automaton, trap: Option<usize>) -> Digraph {
+    let style = match &args.lambda {
+        Some(lambda) => LabelStyle::dot().with_lambda(lambda),
+        None => LabelStyle::dot(),
+    };
+    let mut graph = Digraph::from_automaton(automaton, &style);
+    if args.show_useless {
+        graph = graph.mark_useless();
+    }
+    if let Some(trap) = trap.filter(|_| args.hide_trap) {
+        graph = graph.hide_node(trap);
+    }
+    if args.name_states {
+        graph = graph.with_state_names(&automaton.to_nfa().state_names());
+    }
+    graph
+}
+
+/// Renders every file in the `args.file` directory to `<out>/<name>.dot`, reporting failures
+/// per file instead of stopping at the first one. Fails at the end if any file did.
+fn batch(args: &CliArgs, out: &Path) -> Result<()> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&args.file)
+        .with_context(|| format!("Unable to read directory {}", args.file.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    std::fs::create_dir_all(out)
+        .with_context(|| format!("Unable to create directory {}", out.display()))?;
 
-    let mut file = File::open(p).expect("Unable to open file!");
-    let mut res = String::new();
-    file.read_to_string(&mut res)
-        .expect("Unable to read file contents to string!");
+    let mut failed = 0;
+    for file in &files {
+        let result = std::fs::read_to_string(file)
+            .context("Unable to read file contents to string")
+            .and_then(|input| load(args, &input))
+            .and_then(|(automaton, trap)| {
+                let name = file.file_stem().unwrap_or(file.as_os_str());
+                let target = out.join(format!("{}.dot", name.to_string_lossy()));
+                std::fs::write(&target, format!("{}\n", render(args, &automaton, trap)))
+                    .with_context(|| format!("Unable to write {}", target.display()))
+            });
+        if let Err(e) = result {
+            eprintln!("{}: {:#}", file.display(), e);
+            failed += 1;
+        }
+    }
+    eprintln!("{} rendered, {} failed", files.len() - failed, failed);
+
+    if failed > 0 {
+        anyhow::bail!("Unable to render {} of {} files", failed, files.len());
+    }
+    Ok(())
+}
+
+/// Writes `file:line:column: text` for every match in every line of `input`, with the match
+/// in bold red if `highlight` is set
+fn grep(
+    out: &mut dyn Write,
+    nfa: &NFA,
+    input: &[PathBuf],
+    overlapping: bool,
+    highlight: bool,
+) -> Result<()> {
+    for path in input {
+        for (number, line) in read_file(path)?.lines().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            for range in nfa.find_matches(&chars, overlapping) {
+                let text: String = match highlight {
+                    true => format!(
+                        "{}\x1b[1;31m{}\x1b[0m{}",
+                        chars[..range.start].iter().collect::<String>(),
+                        chars[range.clone()].iter().collect::<String>(),
+                        chars[range.end..].iter().collect::<String>()
+                    ),
+                    false => line.to_owned(),
+                };
+                writeln!(
+                    out,
+                    "{}:{}:{}: {}",
+                    path.display(),
+                    number + 1,
+                    range.start + 1,
+                    text
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn grep_bytes(out: &mut dyn Write, dfa: &bytes::ByteDfa, input: &[PathBuf]) -> Result<()> {
+    for path in input {
+        let mut haystack = Vec::new();
+        match path == Path::new("-") {
+            true => std::io::stdin()
+                .read_to_end(&mut haystack)
+                .context("Unable to read standard input")?,
+            false => File::open(path)
+                .and_then(|mut f| f.read_to_end(&mut haystack))
+                .with_context(|| format!("Unable to read {}", path.display()))?,
+        };
+        for range in dfa.find_iter(&haystack) {
+            writeln!(
+                out,
+                "{}:{}: {}",
+                path.display(),
+                range.start(),
+                haystack[range.clone()].escape_ascii()
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints every step's configurations as `(state, remaining input, stack)`, top of the stack
+/// first, then whether the input was accepted
+fn trace_pda(out: &mut dyn Write, pda: &pda::PDA, input: &str, max_steps: usize) -> Result<()> {
+    let chars: Vec<char> = input.chars().collect();
+    let trace = pda.trace(input, max_steps);
+    for (step, configurations) in trace.iter().enumerate() {
+        let configurations: Vec<String> = configurations
+            .iter()
+            .map(|c| {
+                format!(
+                    "({}, {:?}, {})",
+                    pda.state_name(c.state),
+                    chars[c.position..].iter().collect::<String>(),
+                    c.stack.iter().rev().collect::<String>()
+                )
+            })
+            .collect();
+        writeln!(out, "{}: {}", step, configurations.join(" "))?;
+    }
+    writeln!(out, "accepted: {}", pda.trace_accepts(&trace, input))?;
+
+    Ok(())
+}
+
+/// `round: {0 1} {2}`, with the block that was split and the symbol that split it
+fn describe_step(table: &LabeledTable, round: usize, step: &nerode::PartitionStep) -> String {
+    let blocks: Vec<String> = step
+        .blocks
+        .iter()
+        .map(|block| {
+            let states: Vec<String> = block.iter().map(|s| table.state_name(*s)).collect();
+            format!("{{{}}}", states.join(" "))
+        })
+        .collect();
+    match step.splitter {
+        Some((block, c)) => format!(
+            "{}: {} (split block {} on {:?})",
+            round,
+            blocks.join(" "),
+            block,
+            c
+        ),
+        None => format!("{}: {}", round, blocks.join(" ")),
+    }
+}
+
+/// The [`TableFormat`] named by `--output-format`, a box-drawn table without one
+fn table_format(name: Option<&str>) -> TableFormat {
+    match name {
+        Some("md") => TableFormat::Markdown,
+        Some("csv") => TableFormat::Csv,
+        _ => TableFormat::Text,
+    }
+}
+
+fn to_table(args: &CliArgs, automaton: Automaton) -> Result<LabeledTable> {
+    Ok(match automaton {
+        Automaton::Table(tt) => tt,
+        Automaton::Nfa(nfa) => {
+            with_progress(|progress| nfa.try_determinize_with(&limits(args), progress))
+                .context("Unable to determinize")?
+        }
+    })
+}
+
+/// Runs `f` with a progress bar of the subset construction on standard error, hidden when that
+/// is not a terminal
+fn with_progress<T>(f: impl FnOnce(&dyn Progress) -> T) -> T {
+    let bar = if std::io::stderr().is_terminal() {
+        ProgressBar::new(0)
+    } else {
+        ProgressBar::hidden()
+    };
+    bar.set_style(
+        ProgressStyle::with_template("{msg} {wide_bar} {pos}/{len} sets")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    let result = f(&|stage: Stage, done: usize, total: usize| {
+        bar.set_message(stage.to_string());
+        bar.set_length(total as u64);
+        bar.set_position(done as u64);
+    });
+    bar.finish_and_clear();
+    result
+}
+
+/// The --max-states and --timeout limits
+fn limits(args: &CliArgs) -> Limits {
+    Limits {
+        max_states: args.max_states,
+        timeout: args.timeout,
+        ..Default::default()
+    }
+}
+
+/// The --out file, or standard output
+fn output(args: &CliArgs) -> Result<Box<dyn Write>> {
+    Ok(match &args.out {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("Unable to create {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    })
+}
+
+fn read_file(p: &Path) -> Result<String> {
+    if p == Path::new("-") {
+        let mut res = String::new();
+        std::io::stdin()
+            .read_to_string(&mut res)
+            .context("Unable to read standard input")?;
+        return Ok(res);
+    }
 
-    res
+    std::fs::read_to_string(p).with_context(|| format!("Unable to read {}", p.display()))
 }
@@ -0,0 +1,144 @@
+//! Terminal view for stepping a DFA through an input by hand
+
+use anyhow::Result;
+use fa_viz::{DfaRun, LabelStyle, LabeledTable};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Table},
+};
+
+struct App<'a> {
+    run: DfaRun<'a>,
+}
+
+impl App<'_> {
+    fn draw(&self, frame: &mut Frame) {
+        let [table_area, input_area, status_area, help_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let table = self.run.table();
+        let header = Row::new(
+            ["", "state"].into_iter().map(str::to_owned).chain(
+                (0..table.alphabet().len())
+                    .map(|column| table.symbol_name(column, &LabelStyle::text())),
+            ),
+        )
+        .bold();
+        let rows = table.table().rows.iter().map(|r| {
+            let cells = [
+                if r.accepting { "+" } else { "-" }.to_owned(),
+                table.state_name(r.id),
+            ]
+            .into_iter()
+            .chain(
+                r.transitions
+                    .iter()
+                    .map(|t| t.map_or("∅".to_owned(), |t| table.state_name(t))),
+            );
+            let row = Row::new(cells);
+            match self.run.state() == Some(r.id) {
+                true => row.reversed(),
+                false => row,
+            }
+        });
+        let widths = [Constraint::Length(1), Constraint::Min(5)]
+            .into_iter()
+            .chain(table.alphabet().iter().map(|_| Constraint::Min(3)));
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(header)
+                .block(Block::bordered().title(" Transitions ")),
+            table_area,
+        );
+
+        let position = self.run.position();
+        let consumed: String = self.run.input()[..position].iter().collect();
+        let mut spans = vec![Span::from(consumed).dim()];
+        if let Some((next, rest)) = self.run.remaining().split_first() {
+            spans.push(Span::from(next.to_string()).bold().underlined());
+            spans.push(Span::from(rest.iter().collect::<String>()));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)).block(Block::bordered().title(" Input ")),
+            input_area,
+        );
+
+        let state = self
+            .run
+            .state()
+            .map_or("∅ (missing transition)".to_owned(), |s| {
+                table.state_name(s)
+            });
+        let verdict = match (self.run.is_finished(), self.run.is_accepting()) {
+            (true, true) => Span::from("accepted").green().bold(),
+            (true, false) => Span::from("rejected").red().bold(),
+            (false, true) => Span::from("accepting so far").green(),
+            (false, false) => Span::from("not accepting so far").red(),
+        };
+        let status = Line::from(vec![
+            Span::from(format!(
+                "state {} · step {}/{} · ",
+                state,
+                position,
+                self.run.input().len()
+            )),
+            verdict,
+        ]);
+        frame.render_widget(
+            Paragraph::new(status).block(Block::bordered().title(" Status ")),
+            status_area,
+        );
+
+        frame.render_widget(
+            Paragraph::new("→/l/space step   ←/h back   r/Home reset   e/End run to end   q quit")
+                .dim(),
+            help_area,
+        );
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => {
+                    self.run.step();
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.run.step_back();
+                }
+                KeyCode::Home | KeyCode::Char('r') => self.run.reset(),
+                KeyCode::End | KeyCode::Char('e') => {
+                    self.run.finish();
+                }
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Takes over the terminal until the user quits, starting before the first symbol of `input`
+pub fn run(table: &LabeledTable, input: &str) -> Result<()> {
+    let mut app = App {
+        run: DfaRun::new(table, input),
+    };
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
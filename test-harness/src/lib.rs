@@ -0,0 +1,207 @@
+//! Golden tests over directories of automata fixtures.
+//!
+//! Every file in a fixture directory is an input automaton in any format [`fa_viz::frontend`]
+//! detects, next to a `<file name>.expected` table holding the DFA it should turn into, such as
+//! `ab.dot.expected` for `ab.dot`. Fixtures are found when the test runs, so adding one needs no
+//! code.
+//!
+//! ```ignore
+//! golden_tests!(minimize, "tests/minimize", |automaton| {
+//!     automaton.to_nfa().minimize_brzozowski()
+//! });
+//! ```
+//!
+//! Running the tests with `FA_VIZ_BLESS=1` set, or with `--bless` for a test binary without the
+//! default harness, writes the actual tables as the new expectations instead of comparing.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fa_viz::{Automaton, LabeledTable, ParseOptions, SerializeOptions, frontend};
+
+/// One (input, expected) pair of a fixture directory
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// File name of the input, extension included so `ab.nfa` and `ab.dot` stay apart
+    pub name: String,
+    pub input: PathBuf,
+    /// `<name>.expected`, which may not exist yet
+    pub expected: PathBuf,
+}
+
+/// Fixtures of `dir` in file name order. Hidden files and `.expected` files are not inputs.
+pub fn discover(dir: impl AsRef<Path>) -> Vec<Fixture> {
+    let dir = dir.as_ref();
+    let entries = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Unable to read fixture directory {}: {}", dir.display(), e));
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().is_none_or(|ext| ext != "expected"))
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    inputs.sort();
+    inputs
+        .into_iter()
+        .map(|input| {
+            let name = input
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Fixture {
+                expected: dir.join(format!("{}.expected", name)),
+                name,
+                input,
+            }
+        })
+        .collect()
+}
+
+/// Whether expectations should be rewritten rather than checked
+pub fn bless_mode() -> bool {
+    std::env::var("FA_VIZ_BLESS").is_ok_and(|v| !v.is_empty() && v != "0")
+        || std::env::args().any(|arg| arg == "--bless")
+}
+
+/// How expectations are written, an aligned table with its alphabet. States are renumbered in
+/// breadth-first order first, so tables equal up to state numbering compare equal.
+fn canonical(mut table: LabeledTable) -> String {
+    table.canonicalize();
+    table.serialize(&SerializeOptions {
+        align: true,
+        header: true,
+        ..Default::default()
+    })
+}
+
+/// An actual table that differs from its expectation
+#[derive(Debug)]
+pub struct Mismatch {
+    pub name: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Mismatch {
+    /// Line of the first differing row, counting the alphabet header as line 0
+    pub fn first_difference(&self) -> Option<usize> {
+        let (expected, actual): (Vec<&str>, Vec<&str>) = (
+            self.expected.lines().collect(),
+            self.actual.lines().collect(),
+        );
+        (0..expected.len().max(actual.len())).find(|i| expected.get(*i) != actual.get(*i))
+    }
+}
+
+/// Both tables side by side, differing rows marked with `>`
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let expected: Vec<&str> = self.expected.lines().collect();
+        let actual: Vec<&str> = self.actual.lines().collect();
+        let width = expected
+            .iter()
+            .map(|l| l.chars().count())
+            .chain(["expected".len()])
+            .max()
+            .unwrap_or_default();
+        match self.first_difference() {
+            Some(0) => writeln!(f, "{}: alphabets differ", self.name)?,
+            Some(line) => writeln!(f, "{}: first differing row is row {}", self.name, line)?,
+            None => writeln!(f, "{}: tables differ", self.name)?,
+        }
+        writeln!(f, "  {:<width$} | actual", "expected", width = width)?;
+        for i in 0..expected.len().max(actual.len()) {
+            let (left, right) = (expected.get(i), actual.get(i));
+            writeln!(
+                f,
+                "{} {:<width$} | {}",
+                if left == right { ' ' } else { '>' },
+                left.unwrap_or(&""),
+                right.unwrap_or(&""),
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `transform` on `fixture`'s input and compares the result with its expectation, or
+/// writes the expectation in [`bless_mode`]. Fixtures that cannot be read are reported the
+/// same way as mismatches.
+pub fn check(
+    fixture: &Fixture,
+    transform: impl Fn(Automaton) -> LabeledTable,
+) -> Result<(), String> {
+    let input = fs::read_to_string(&fixture.input)
+        .map_err(|e| format!("{}: Unable to read input: {}", fixture.name, e))?;
+    let parser = frontend::detect(&input)
+        .ok_or_else(|| format!("{}: Unable to detect input format", fixture.name))?;
+    let automaton = parser
+        .parse(&input)
+        .map_err(|e| format!("{}: Unable to parse input: {}", fixture.name, e))?;
+    let actual = canonical(transform(automaton));
+
+    if bless_mode() {
+        return fs::write(&fixture.expected, actual)
+            .map_err(|e| format!("{}: Unable to write expectation: {}", fixture.name, e));
+    }
+    let expected = fs::read_to_string(&fixture.expected).map_err(|e| {
+        format!(
+            "{}: Unable to read {} ({}), run with FA_VIZ_BLESS=1 to create it",
+            fixture.name,
+            fixture.expected.display(),
+            e
+        )
+    })?;
+    let expected = LabeledTable::parse(&expected, &ParseOptions::default())
+        .map_err(|e| format!("{}: Unable to parse expectation: {}", fixture.name, e))?;
+
+    let mismatch = Mismatch {
+        name: fixture.name.clone(),
+        expected: canonical(expected),
+        actual,
+    };
+    match mismatch.expected == mismatch.actual {
+        true => Ok(()),
+        false => Err(mismatch.to_string()),
+    }
+}
+
+/// [`check`]s every fixture of `dir`, panicking with every failure at once. An empty directory
+/// fails too, since it is most likely a wrong path.
+pub fn run(dir: impl AsRef<Path>, transform: impl Fn(Automaton) -> LabeledTable) {
+    let dir = dir.as_ref();
+    let fixtures = discover(dir);
+    assert!(!fixtures.is_empty(), "No fixtures in {}", dir.display());
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| check(fixture, &transform).err())
+        .collect();
+    if !failures.is_empty() {
+        panic!(
+            "{} of {} fixtures in {} failed\n\n{}",
+            failures.len(),
+            fixtures.len(),
+            dir.display(),
+            failures.join("\n")
+        );
+    }
+}
+
+/// Declares a test running [`run`] over a fixture directory relative to the crate root
+#[macro_export]
+macro_rules! golden_tests {
+    ($name:ident, $dir:expr, $transform:expr $(,)?) => {
+        #[test]
+        fn $name() {
+            $crate::run(
+                ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($dir),
+                $transform,
+            );
+        }
+    };
+}
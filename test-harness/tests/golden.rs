@@ -0,0 +1,10 @@
+use fa_viz::Automaton;
+use fa_viz_test_harness::golden_tests;
+
+golden_tests!(determinize, "tests/determinize", |automaton: Automaton| {
+    automaton.to_nfa().determinize()
+});
+
+golden_tests!(minimize, "tests/minimize", |automaton: Automaton| {
+    automaton.to_nfa().minimize_brzozowski()
+});
@@ -0,0 +1,118 @@
+//! The harness on fixture directories made up on the spot
+
+use std::fs;
+use std::path::PathBuf;
+
+use fa_viz::Automaton;
+use fa_viz_test_harness::{Mismatch, bless_mode, check, discover};
+
+const ENDS_IN_A: &str = "2 # a b\n- 0 0 a b\n- 0 1 a\n+ 1 1\n";
+const ENDS_IN_A_DFA: &str = "#   a b\n- 0 1 0\n+ 1 1 0\n";
+
+/// An empty directory of its own for every test
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fa-viz-harness-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn determinize(automaton: Automaton) -> fa_viz::LabeledTable {
+    automaton.to_nfa().determinize()
+}
+
+#[test]
+fn discover_keys_expectations_by_file_name() {
+    let dir = scratch("discover");
+    for file in ["ab.nfa", "ab.dot", "ab.nfa.expected", ".hidden"] {
+        fs::write(dir.join(file), "").unwrap();
+    }
+    let fixtures = discover(&dir);
+    let names: Vec<&str> = fixtures.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["ab.dot", "ab.nfa"]);
+    assert_eq!(fixtures[0].expected, dir.join("ab.dot.expected"));
+    assert_eq!(fixtures[1].expected, dir.join("ab.nfa.expected"));
+}
+
+#[test]
+fn matching_expectation_passes() {
+    // Blessing writes expectations instead of checking them
+    if bless_mode() {
+        return;
+    }
+    let dir = scratch("matching");
+    fs::write(dir.join("ends_in_a.nfa"), ENDS_IN_A).unwrap();
+    fs::write(dir.join("ends_in_a.nfa.expected"), ENDS_IN_A_DFA).unwrap();
+    let fixtures = discover(&dir);
+    assert_eq!(check(&fixtures[0], determinize), Ok(()));
+}
+
+#[test]
+fn expectation_equal_up_to_numbering_passes() {
+    if bless_mode() {
+        return;
+    }
+    let dir = scratch("renumbered");
+    fs::write(dir.join("ends_in_a.nfa"), ENDS_IN_A).unwrap();
+    fs::write(
+        dir.join("ends_in_a.nfa.expected"),
+        "#   a b\n- 5 7 5\n+ 7 7 5\n",
+    )
+    .unwrap();
+    assert_eq!(check(&discover(&dir)[0], determinize), Ok(()));
+}
+
+#[test]
+fn differing_expectation_fails_at_first_differing_row() {
+    if bless_mode() {
+        return;
+    }
+    let dir = scratch("differing");
+    fs::write(dir.join("ends_in_a.nfa"), ENDS_IN_A).unwrap();
+    fs::write(
+        dir.join("ends_in_a.nfa.expected"),
+        "#   a b\n- 0 1 0\n- 1 1 0\n",
+    )
+    .unwrap();
+    let error = check(&discover(&dir)[0], determinize).unwrap_err();
+    assert!(
+        error.contains("ends_in_a.nfa: first differing row is row 2"),
+        "{}",
+        error
+    );
+    assert!(error.contains("> - 1 1 0"), "{}", error);
+}
+
+#[test]
+fn missing_expectation_fails() {
+    if bless_mode() {
+        return;
+    }
+    let dir = scratch("missing");
+    fs::write(dir.join("ends_in_a.nfa"), ENDS_IN_A).unwrap();
+    let error = check(&discover(&dir)[0], determinize).unwrap_err();
+    assert!(error.contains("FA_VIZ_BLESS=1"), "{}", error);
+}
+
+#[test]
+fn mismatch_reports_alphabet_and_row_differences() {
+    let mismatch = |expected: &str, actual: &str| Mismatch {
+        name: "m".to_owned(),
+        expected: expected.to_owned(),
+        actual: actual.to_owned(),
+    };
+    assert_eq!(
+        mismatch("# a\n- 0 0\n", "# b\n- 0 0\n").first_difference(),
+        Some(0)
+    );
+    assert_eq!(
+        mismatch("# a\n- 0 0\n", "# a\n- 0 0\n+ 1 1\n").first_difference(),
+        Some(2)
+    );
+    assert_eq!(mismatch("# a\n", "# a\n").first_difference(), None);
+    assert!(
+        mismatch("# a\n- 0 0\n", "# b\n- 0 0\n")
+            .to_string()
+            .starts_with("m: alphabets differ")
+    );
+}
@@ -0,0 +1,37 @@
+use fa_viz::codegen::{self, CodegenError};
+use fa_viz::{LabeledTable, TableBuilder};
+
+fn table() -> LabeledTable {
+    TableBuilder::new(['a'])
+        .state(0, true, [('a', 0)])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn keywords_are_rejected_for_both_targets() {
+    let table = table();
+    for name in [
+        "fn", "match", "type", "loop", "self", "int", "static", "switch", "void",
+    ] {
+        assert!(
+            matches!(codegen::rust(&[(name, &table)]), Err(CodegenError::ReservedName(n)) if n == name),
+            "{}",
+            name
+        );
+        assert!(
+            matches!(codegen::c(&[(name, &table)], "x.h"), Err(CodegenError::ReservedName(n)) if n == name),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn names_containing_keywords_are_accepted() {
+    let table = table();
+    for name in ["fn_name", "matcher", "int2", "my_type"] {
+        assert!(codegen::rust(&[(name, &table)]).is_ok(), "{}", name);
+        assert!(codegen::c(&[(name, &table)], "x.h").is_ok(), "{}", name);
+    }
+}
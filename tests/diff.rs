@@ -0,0 +1,49 @@
+use fa_viz::{TableBuilder, diff};
+
+#[test]
+fn only_in_lists_use_canonical_ids() {
+    // The same automaton numbered differently, the first with an extra unreachable state
+    let first = TableBuilder::new(['a'])
+        .state(0, false, [('a', 1)])
+        .state(1, true, [('a', 0)])
+        .state(2, true, [('a', 2)])
+        .build()
+        .unwrap();
+    let second = TableBuilder::new(['a'])
+        .state(7, false, [('a', 3)])
+        .state(3, true, [('a', 7)])
+        .build()
+        .unwrap();
+    let diff = diff::diff(&first, &second);
+    assert!(diff.is_equivalent());
+    assert_eq!(diff.only_in_first, [2]);
+    assert!(diff.only_in_second.is_empty());
+
+    let renumbered = TableBuilder::new(['a'])
+        .state(5, false, [('a', 9)])
+        .state(9, true, [('a', 5)])
+        .state(4, false, [('a', 4)])
+        .build()
+        .unwrap();
+    let diff = diff::diff(&second, &renumbered);
+    assert_eq!(diff.only_in_second, [2]);
+    assert!(diff.to_string().contains("only in second: 2"), "{}", diff);
+}
+
+#[test]
+fn display_spells_symbols_as_text() {
+    let first = TableBuilder::new([' ', '<'])
+        .state(0, false, [(' ', 1)])
+        .state(1, true, [])
+        .build()
+        .unwrap();
+    let second = TableBuilder::new([' ', '<'])
+        .state(0, false, [(' ', 1), ('<', 1)])
+        .state(1, true, [])
+        .build()
+        .unwrap();
+    let text = diff::diff(&first, &second).to_string();
+    assert!(text.contains("added: 0,0 -<-> ∅,1"), "{}", text);
+    assert!(!text.contains("&lt;"), "{}", text);
+    assert!(!text.contains("<i>"), "{}", text);
+}
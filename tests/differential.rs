@@ -0,0 +1,174 @@
+//! The subset construction against a brute-force NFA simulator, on random NFAs
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use fa_viz::testing::{RandomNfaConfig, Rng, random_nfa};
+use fa_viz::{DfaRun, FiniteAutomaton, LabeledTable, Minimizer, NFA, Transition, nerode};
+
+/// Depth-first search over (state, position) pairs, following lambda edges for free. Pairs are
+/// visited once, so lambda cycles end.
+fn simulate(nfa: &NFA, input: &[char]) -> bool {
+    let Some(start) = nfa.start_state() else {
+        return false;
+    };
+    let edges = nfa.edges();
+    let mut seen = BTreeSet::new();
+    let mut stack = vec![(start, 0)];
+    while let Some((state, position)) = stack.pop() {
+        if !seen.insert((state, position)) {
+            continue;
+        }
+        if position == input.len() && nfa.is_accepting_state(state) {
+            return true;
+        }
+        for (from, transition, to) in &edges {
+            if *from != state {
+                continue;
+            }
+            match transition {
+                Transition::Lambda => stack.push((*to, position)),
+                Transition::Char(c) if input.get(position) == Some(c) => {
+                    stack.push((*to, position + 1))
+                }
+                Transition::Char(_) => {}
+            }
+        }
+    }
+    false
+}
+
+fn dfa_accepts(table: &LabeledTable, input: &[char]) -> bool {
+    let mut run = DfaRun::new(table, &input.iter().collect::<String>());
+    run.finish();
+    run.accepted()
+}
+
+/// Every string of up to 4 symbols, then random ones up to 12
+fn inputs(alphabet: &[char], rng: &mut Rng) -> Vec<Vec<char>> {
+    let mut inputs = vec![Vec::new()];
+    let mut layer = vec![Vec::new()];
+    for _ in 0..4 {
+        layer = layer
+            .iter()
+            .flat_map(|word: &Vec<char>| {
+                alphabet.iter().map(move |c| {
+                    let mut word = word.clone();
+                    word.push(*c);
+                    word
+                })
+            })
+            .collect();
+        inputs.extend(layer.iter().cloned());
+    }
+    for _ in 0..100 {
+        let len = (rng.next_u64() % 13) as usize;
+        inputs.push(
+            (0..len)
+                .map(|_| alphabet[(rng.next_u64() % alphabet.len() as u64) as usize])
+                .collect(),
+        );
+    }
+    inputs
+}
+
+#[test]
+fn determinized_matches_simulator() {
+    let mut rng = Rng::new(0x5eed);
+    for (i, lambda_density) in [0.0, 0.05, 0.3].into_iter().cycle().take(60).enumerate() {
+        let config = RandomNfaConfig {
+            states: 2 + i % 9,
+            alphabet_size: 1 + i % 3,
+            lambda_density,
+            ..Default::default()
+        };
+        let nfa = random_nfa(&config, &mut rng);
+        let dfa = nfa.determinize();
+        let minimal = nfa.minimize_brzozowski();
+        for input in inputs(&nfa.symbols(), &mut rng) {
+            let expected = simulate(&nfa, &input);
+            let word: String = input.iter().collect();
+            assert_eq!(
+                dfa_accepts(&dfa, &input),
+                expected,
+                "DFA on {:?}\n{}",
+                word,
+                nfa.serialize()
+            );
+            assert_eq!(
+                dfa_accepts(&minimal, &input),
+                expected,
+                "minimal DFA on {:?}\n{}",
+                word,
+                nfa.serialize()
+            );
+            assert_eq!(nfa.accepts(&word), expected, "{:?}", word);
+        }
+    }
+}
+
+#[test]
+fn minimized_has_no_equivalent_states() {
+    let mut rng = Rng::new(0xb22);
+    for i in 0..60 {
+        let config = RandomNfaConfig {
+            states: 2 + i % 9,
+            alphabet_size: 1 + i % 3,
+            ..Default::default()
+        };
+        let nfa = random_nfa(&config, &mut rng);
+        let minimal = nfa.minimize_brzozowski();
+        let merged = nerode::classes(&minimal)
+            .classes
+            .into_iter()
+            .find(|class| class.states.len() > 1);
+        assert!(
+            merged.is_none(),
+            "states {:?} are equivalent\n{}",
+            merged.map(|c| c.states),
+            nfa.serialize()
+        );
+        let classes = nerode::classes(&nfa.determinize()).classes;
+        let live = classes.iter().filter(|c| !c.states.is_empty()).count();
+        assert!(minimal.table().rows.len() <= live, "{}", nfa.serialize());
+    }
+}
+
+#[test]
+fn minimizers_agree() {
+    // The empty language keeps its start state either way
+    let empty = NFA::from_str("2 # a\n- 0 1 a\n- 1 0 a\n").unwrap();
+    for minimizer in [Minimizer::Brzozowski, Minimizer::PartitionRefinement] {
+        let minimal = empty.minimize(minimizer);
+        assert_eq!(minimal.table().rows.len(), 1, "{:?}", minimizer);
+        assert!(!minimal.table().rows[0].accepting, "{:?}", minimizer);
+    }
+
+    let mut rng = Rng::new(0x1557);
+    for i in 0..60 {
+        let config = RandomNfaConfig {
+            states: 2 + i % 9,
+            alphabet_size: 1 + i % 3,
+            ..Default::default()
+        };
+        let nfa = random_nfa(&config, &mut rng);
+        let brzozowski = nfa.minimize(Minimizer::Brzozowski);
+        let refined = nfa.minimize(Minimizer::PartitionRefinement);
+        assert_eq!(
+            brzozowski.table().rows.len(),
+            refined.table().rows.len(),
+            "{}",
+            nfa.serialize()
+        );
+        assert!(
+            NFA::from(&brzozowski).is_equivalent(&NFA::from(&refined)),
+            "{}",
+            nfa.serialize()
+        );
+        assert!(
+            NFA::from(&refined).is_equivalent(&nfa),
+            "{}",
+            nfa.serialize()
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use fa_viz::{Digraph, FiniteAutomaton, LabelStyle, NFA, dot, mermaid};
+
+/// Label of the start state and the number of states
+fn start_and_size(nfa: &NFA) -> (String, usize) {
+    let start = nfa.start_state().expect("graph has a start state");
+    (nfa.labels()[&start].clone(), nfa.state_ids().len())
+}
+
+#[test]
+fn invisible_source_is_a_start_marker() {
+    for marker in [
+        "s [shape=point]",
+        "s [shape=none]",
+        "s [style=invis]",
+        "s [label=\"\"]",
+    ] {
+        let nfa = dot::parse(&format!(
+            "digraph {{ {}; s -> q0; q0 -> q1 [label=\"a\"]; q1 [shape=doublecircle] }}",
+            marker
+        ))
+        .unwrap();
+        assert_eq!(start_and_size(&nfa), ("q0".to_owned(), 2), "{}", marker);
+        assert!(nfa.accepts("a"), "{}", marker);
+    }
+}
+
+#[test]
+fn visible_node_named_start_is_a_state() {
+    let nfa = dot::parse(
+        "digraph { start -> q [label=\"a\"]; q -> start [label=\"b\"]; q [shape=doublecircle] }",
+    )
+    .unwrap();
+    assert_eq!(start_and_size(&nfa), ("start".to_owned(), 2));
+    assert!(nfa.accepts("aba"));
+}
+
+#[test]
+fn invisible_node_with_incoming_edges_is_a_state() {
+    let nfa = dot::parse(
+        "digraph { a -> p [label=\"x\"]; p -> a [label=\"y\"]; p [shape=point]; a [shape=doublecircle] }",
+    )
+    .unwrap();
+    assert_eq!(start_and_size(&nfa), ("a".to_owned(), 2));
+    assert!(nfa.accepts("xy"));
+    assert!(!nfa.accepts("x"));
+}
+
+#[test]
+fn separator_symbols_round_trip() {
+    // Accepts exactly "|" and ","
+    let nfa = NFA::from_str("2 # | ,\n- 0 1 | ,\n+ 1 1\n").unwrap();
+    let text = Digraph::from(&nfa).to_string();
+    assert!(text.contains("&#44;|&#124;"), "{}", text);
+    let parsed = dot::parse(&text).unwrap_or_else(|e| panic!("{}\n{}", text, e));
+    assert!(parsed.is_equivalent(&nfa), "{}", text);
+    assert!(parsed.accepts("|") && parsed.accepts(","));
+    assert!(!parsed.accepts(""));
+
+    let label = Digraph::from_nfa(&nfa, &LabelStyle::text()).edges[&(0, [1].into())].clone();
+    assert_eq!(label, "#44;|#124;");
+    let parsed = mermaid::parse(&format!(
+        "stateDiagram-v2\n[*] --> q0\nq0 --> q1 : {}\nq1 --> [*]\n",
+        label
+    ))
+    .unwrap();
+    assert!(parsed.is_equivalent(&nfa));
+}
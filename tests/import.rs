@@ -0,0 +1,32 @@
+use std::str::FromStr;
+
+use fa_viz::{FiniteAutomaton, NFA, dot, mermaid};
+
+#[test]
+fn leading_zero_names_are_not_numbers() {
+    let nfa =
+        mermaid::parse("stateDiagram-v2\n[*] --> 1\n1 --> 01 : a\n01 --> 001 : b\n001 --> [*]\n")
+            .unwrap();
+    assert_eq!(nfa.state_ids().len(), 3);
+    assert!(nfa.accepts("ab"));
+    assert!(!nfa.accepts("a"));
+    let names: Vec<&str> = nfa.labels().values().map(String::as_str).collect();
+    assert_eq!(names, ["1", "01", "001"]);
+}
+
+#[test]
+fn exact_numbers_keep_their_ids() {
+    let nfa =
+        dot::parse("digraph { s [shape=point]; s -> 0; 0 -> 2 [label=a]; 2 [shape=doublecircle] }")
+            .unwrap();
+    assert_eq!(nfa.state_ids(), [0, 2].into());
+    assert!(nfa.labels().is_empty());
+}
+
+#[test]
+fn nfa_definition_distinguishes_padded_names() {
+    let nfa = NFA::from_str("3 # a\n- 0 00 a\n- 00 000 a\n+ 000 000\n").unwrap();
+    assert_eq!(nfa.state_ids().len(), 3);
+    assert!(nfa.accepts("aa"));
+    assert!(!nfa.accepts("a"));
+}
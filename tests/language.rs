@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use fa_viz::NFA;
+
+#[test]
+fn finite_language_with_dead_cycle_ends() {
+    // `b` leads into a loop that never accepts, so only `a` and `ab` are accepted
+    let nfa = NFA::from_str(
+        "\
+4 # a b
+- 0 1 a
+- 0 3 b
++ 1 2 b
++ 2 2
+- 3 3 a
+- 3 3 b
+",
+    )
+    .unwrap();
+    let words: Vec<String> = nfa.enumerate_accepted(usize::MAX).collect();
+    assert_eq!(words, ["a", "ab"]);
+}
+
+#[test]
+fn empty_language_ends_immediately() {
+    let nfa = NFA::from_str(
+        "\
+2 # a
+- 0 1 a
+- 1 0 a
+",
+    )
+    .unwrap();
+    assert_eq!(nfa.enumerate_accepted(usize::MAX).next(), None);
+}
+
+#[test]
+fn infinite_language_is_shortest_first() {
+    let nfa = NFA::from_str(
+        "\
+1 # a b
++ 0 0 a
+",
+    )
+    .unwrap();
+    let words: Vec<String> = nfa.enumerate_accepted(usize::MAX).take(3).collect();
+    assert_eq!(words, ["", "a", "aa"]);
+}
@@ -0,0 +1,26 @@
+use std::str::FromStr;
+
+use fa_viz::{NFA, ParseErrorKind};
+
+const BROKEN_ROW: &str = "\
+2 # a b
+- 0 1 a
++ 1
++ 1 1 b
+";
+
+#[test]
+fn lenient_parsing_skips_broken_rows() {
+    assert!(NFA::from_str(BROKEN_ROW).is_err());
+    let (nfa, skipped) = NFA::parse_lenient(BROKEN_ROW).unwrap();
+    assert_eq!(skipped.len(), 1);
+    assert!(matches!(skipped[0].kind, ParseErrorKind::ColumnMismatch));
+    assert_eq!(skipped[0].line, 3);
+    assert!(nfa.accepts("abb"));
+    assert!(!nfa.accepts("b"));
+}
+
+#[test]
+fn lenient_parsing_still_needs_a_header() {
+    assert!(NFA::parse_lenient("").is_err());
+}
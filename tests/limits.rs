@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use fa_viz::{Automaton, LimitExceeded, Limits, NFA, report};
+
+/// Strings whose `n`th symbol from the end is `a`, which take 2^n DFA states
+fn nth_from_end(n: usize) -> NFA {
+    let mut definition = format!("{} # a b\n- 0 0 a b\n- 0 1 a\n", n + 1);
+    for state in 1..n {
+        definition += &format!("- {} {} a b\n", state, state + 1);
+    }
+    definition += &format!("+ {} {}\n", n, n);
+    NFA::from_str(&definition).unwrap()
+}
+
+fn few_states() -> Limits {
+    Limits {
+        max_states: Some(8),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn worksheet_within_limits_matches_unlimited() {
+    let nfa = nth_from_end(3);
+    let worksheet = nfa.try_worksheet_with(&few_states(), &()).unwrap();
+    assert_eq!(worksheet.rows.len(), nfa.worksheet().rows.len());
+    assert_eq!(worksheet.rows.len(), nfa.determinize().table().rows.len());
+}
+
+#[test]
+fn worksheet_and_overlay_stop_at_limit() {
+    let nfa = nth_from_end(6);
+    assert!(matches!(
+        nfa.try_worksheet_with(&few_states(), &()),
+        Err(LimitExceeded::States(8))
+    ));
+    assert!(matches!(
+        nfa.try_subset_overlay_with(&few_states(), &()),
+        Err(LimitExceeded::States(8))
+    ));
+}
+
+#[test]
+fn report_stops_at_limit() {
+    let automaton = Automaton::Nfa(nth_from_end(6));
+    assert!(matches!(
+        report::try_html_with("blowup", &automaton, 3, &few_states(), &()),
+        Err(LimitExceeded::States(8))
+    ));
+    let small = Automaton::Nfa(nth_from_end(2));
+    assert_eq!(
+        report::try_html_with("small", &small, 3, &few_states(), &()).unwrap(),
+        report::html("small", &small, 3)
+    );
+}
@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use fa_viz::{
+    pda::{PDA, PdaError},
+    tm::{TM, TmError},
+};
+
+#[test]
+fn both_formats_report_the_same_shape_errors() {
+    assert!(matches!(
+        TM::from_str("start: q\n- q q a a R\n- q q b\n"),
+        Err(TmError::ColumnMismatch(3))
+    ));
+    assert!(matches!(
+        PDA::from_str("start: q\n- q q a Z AZ\n- q q b\n"),
+        Err(PdaError::ColumnMismatch(3))
+    ));
+    assert!(matches!(
+        TM::from_str("blank: #\n\n"),
+        Err(TmError::NoTransitions)
+    ));
+    assert!(matches!(
+        PDA::from_str("lambda: e\n"),
+        Err(PdaError::NoTransitions)
+    ));
+    assert!(matches!(
+        TM::from_str("start: r\n- q q a a R\n"),
+        Err(TmError::UnknownStart(s)) if s == "r"
+    ));
+    assert!(matches!(
+        PDA::from_str("start: r\n- q q a Z Z\n"),
+        Err(PdaError::UnknownStart(s)) if s == "r"
+    ));
+}
+
+#[test]
+fn directives_only_lead_the_definition() {
+    let tm = TM::from_str("start: b\nblank: #\n- a b x x R\n+ b b # # S\n").unwrap();
+    assert_eq!(tm.state_name(tm.start().unwrap()), "b");
+    let pda = PDA::from_str("start: b\nlambda: e\ninitial: $\n- a b e $ $\n+ b b e e e\n").unwrap();
+    assert_eq!(pda.state_name(pda.start().unwrap()), "b");
+    assert!(pda.accepts("", 8));
+
+    // After the first row a `key: value` line is a row like any other
+    assert!(matches!(
+        TM::from_str("- a b x x R\nstart: b\n"),
+        Err(TmError::ColumnMismatch(2))
+    ));
+}
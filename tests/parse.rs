@@ -0,0 +1,22 @@
+use std::str::FromStr;
+
+use fa_viz::{FiniteAutomaton, NFA};
+
+#[test]
+fn target_only_states_exist() {
+    let nfa = NFA::from_str("2 # a\n- 0 1 a\n").unwrap();
+    assert_eq!(nfa.state_ids().len(), 2);
+    assert!(nfa.states().contains_key(&1));
+}
+
+#[test]
+fn lower_numbered_target_is_not_the_start() {
+    // Only 1 has a row, so it is the start even though 0 is lower
+    let nfa = NFA::from_str("2 # a b\n+ 1 0 a\n- 1 1 b\n").unwrap();
+    assert_eq!(nfa.state_ids().len(), 2);
+    let start = nfa.start_state().unwrap();
+    assert_eq!(nfa.labels().get(&start).map(String::as_str), Some("1"));
+    assert!(nfa.accepts(""));
+    assert!(nfa.accepts("b"));
+    assert!(!nfa.accepts("a"));
+}
@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use fa_viz::pda::PDA;
+
+/// `a^n b^n`
+const BALANCED: &str = "\
+- 0 0 a Z AZ
+- 0 0 a A AA
+- 0 1 b A &
+- 1 1 b A &
+- 0 2 & Z Z
+- 1 2 & Z Z
++ 2 2 & Z Z
+";
+
+#[test]
+fn trace_accepts_agrees_with_accepts() {
+    let pda = PDA::from_str(BALANCED).unwrap();
+    for input in ["", "ab", "aabb", "aaabbb", "a", "abb", "ba", "aabbb"] {
+        let trace = pda.trace(input, 64);
+        assert_eq!(
+            pda.trace_accepts(&trace, input),
+            pda.accepts(input, 64),
+            "{:?}",
+            input
+        );
+        assert_eq!(
+            pda.accepts(input, 64),
+            input.len() % 2 == 0
+                && *input == "a".repeat(input.len() / 2) + &"b".repeat(input.len() / 2),
+            "{:?}",
+            input
+        );
+    }
+}
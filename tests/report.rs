@@ -0,0 +1,22 @@
+use std::str::FromStr;
+
+use fa_viz::{Automaton, NFA, report};
+
+#[test]
+fn finite_language_lists_every_example() {
+    // More examples are asked for than the language has, and `b` loops forever without accepting
+    let nfa = NFA::from_str(
+        "\
+3 # a b
++ 0 1 a
+- 0 2 b
++ 1 1
+- 2 2 a
+- 2 2 b
+",
+    )
+    .unwrap();
+    let html = report::html("finite", &Automaton::Nfa(nfa), 10);
+    assert_eq!(html.matches("<li>").count(), 2);
+    assert!(html.contains("<li><code>&quot;a&quot;</code></li>"));
+}
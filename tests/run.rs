@@ -0,0 +1,22 @@
+use fa_viz::{DfaRun, TableBuilder};
+
+#[test]
+fn empty_table_is_never_stuck() {
+    let table = TableBuilder::new(['a']).build().unwrap();
+    let mut run = DfaRun::new(&table, "aa");
+    assert_eq!(run.stuck_at(), None);
+    run.finish();
+    assert_eq!(run.stuck_at(), None);
+    assert!(!run.accepted());
+}
+
+#[test]
+fn stuck_at_missing_transition() {
+    let table = TableBuilder::new(['a', 'b'])
+        .state(0, true, [('a', 0)])
+        .build()
+        .unwrap();
+    let mut run = DfaRun::new(&table, "aaba");
+    run.finish();
+    assert_eq!(run.stuck_at(), Some(2));
+}
@@ -0,0 +1,33 @@
+use std::str::FromStr;
+
+use fa_viz::{NFA, Stats};
+
+#[test]
+fn components_of_cycles_and_chains() {
+    // 0 and 1 form a cycle, 2 a cycle of its own, 3 and 4 are singletons
+    let nfa =
+        NFA::from_str("5 # a b\n- 0 1 a\n- 1 0 a\n- 1 2 b\n- 2 2 a\n- 2 3 b\n+ 3 4 a\n+ 4 4\n")
+            .unwrap();
+    assert_eq!(Stats::from(&nfa).strongly_connected_components, 4);
+}
+
+#[test]
+fn long_chain_into_cycle() {
+    // Every state leads back to the start through the last, so they form one component
+    let n = 300;
+    let mut definition = format!("{} # a\n", n);
+    for state in 0..n {
+        definition += &format!("- {} {} a\n", state, (state + 1) % n);
+    }
+    let nfa = NFA::from_str(&definition).unwrap();
+    assert_eq!(Stats::from(&nfa).strongly_connected_components, 1);
+
+    // Without the edge back every state is its own component
+    let mut definition = format!("{} # a\n", n);
+    for state in 0..n - 1 {
+        definition += &format!("- {} {} a\n", state, state + 1);
+    }
+    definition += &format!("+ {} {}\n", n - 1, n - 1);
+    let nfa = NFA::from_str(&definition).unwrap();
+    assert_eq!(Stats::from(&nfa).strongly_connected_components, n);
+}
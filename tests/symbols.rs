@@ -0,0 +1,114 @@
+//! Symbols that are whitespace, quote, markup or label separator characters, or outside ASCII,
+//! through every format that writes or reads them
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use fa_viz::{
+    Digraph, FiniteAutomaton, LabelStyle, LabeledTable, NFA, ParseOptions, SerializeOptions,
+    TableFormat, Transition, dot, mermaid,
+};
+
+const AWKWARD: [char; 10] = [' ', '\t', '\n', '"', '<', '&', '|', ',', 'é', '→'];
+
+/// Accepts a single awkward symbol followed by any number of `a`
+fn nfa() -> NFA {
+    let mut definition = String::from("2 # a");
+    for c in AWKWARD {
+        definition += &format!(" {}", Transition::Char(c).render(&LabelStyle::text()));
+    }
+    definition += "\n- 0 1";
+    for c in AWKWARD {
+        definition += &format!(" {}", Transition::Char(c).render(&LabelStyle::text()));
+    }
+    definition += "\n+ 1 1 a\n";
+    NFA::from_str(&definition).unwrap_or_else(|e| panic!("{}\n{}", definition, e))
+}
+
+fn alphabet(automaton: &impl FiniteAutomaton) -> BTreeSet<char> {
+    automaton.symbols().into_iter().collect()
+}
+
+fn assert_same_language(nfa: &NFA, other: &NFA, text: &str) {
+    assert_eq!(alphabet(other), alphabet(nfa), "{}", text);
+    assert!(nfa.is_equivalent(other), "{}", text);
+    for c in AWKWARD {
+        assert!(other.accepts(&format!("{}aa", c)), "{:?} in {}", c, text);
+    }
+}
+
+#[test]
+fn nfa_round_trips() {
+    let nfa = nfa();
+    let text = nfa.serialize();
+    let parsed = NFA::from_str(&text).unwrap_or_else(|e| panic!("{}\n{}", text, e));
+    assert_same_language(&nfa, &parsed, &text);
+}
+
+#[test]
+fn table_round_trips() {
+    let table = nfa().determinize();
+    for align in [false, true] {
+        let text = table.serialize(&SerializeOptions {
+            align,
+            header: true,
+            ..Default::default()
+        });
+        let parsed = LabeledTable::parse(&text, &ParseOptions::default())
+            .unwrap_or_else(|e| panic!("{}\n{}", text, e));
+        assert_eq!(parsed.alphabet(), table.alphabet(), "{}", text);
+        assert_same_language(&nfa(), &NFA::from(&parsed), &text);
+    }
+}
+
+#[test]
+fn table_renders_whitespace_by_name() {
+    let table = nfa().determinize();
+    for format in [TableFormat::Text, TableFormat::Markdown, TableFormat::Csv] {
+        let text = table.render(format);
+        for name in ["SP", "TAB", "NL"] {
+            assert!(text.contains(name), "{} missing from {}", name, text);
+        }
+        assert!(!text.contains('\t'), "{}", text);
+        let lines = match format {
+            TableFormat::Csv => table.table().rows.len() + 1,
+            TableFormat::Markdown => table.table().rows.len() + 2,
+            TableFormat::Text => table.table().rows.len() + 4,
+        };
+        assert_eq!(text.lines().count(), lines, "{}", text);
+    }
+    let csv = table.render(TableFormat::Csv);
+    assert!(csv.contains(",\"\"\"\","), "{}", csv);
+}
+
+#[test]
+fn dot_escapes_labels_and_round_trips() {
+    let nfa = nfa();
+    let text = Digraph::from(&nfa).to_string();
+    for escaped in [
+        "&lt;",
+        "&amp;",
+        "&quot;",
+        "&#124;",
+        "&#44;",
+        "<i>SP</i>",
+        "<i>TAB</i>",
+        "<i>NL</i>",
+    ] {
+        assert!(text.contains(escaped), "{} missing from {}", escaped, text);
+    }
+    assert!(!text.contains('\t'), "{}", text);
+    let parsed = dot::parse(&text).unwrap_or_else(|e| panic!("{}\n{}", text, e));
+    assert_same_language(&nfa, &parsed, &text);
+}
+
+#[test]
+fn mermaid_reads_text_labels() {
+    let label = &Digraph::from_nfa(&nfa(), &LabelStyle::text()).edges[&(0, [1].into())];
+    let text = format!(
+        "stateDiagram-v2\n[*] --> q0\nq0 --> q1 : {}\nq1 --> q1 : a\nq1 --> [*]\n",
+        label
+    );
+    let parsed = mermaid::parse(&text).unwrap_or_else(|e| panic!("{}\n{}", text, e));
+    assert_same_language(&nfa(), &parsed, &text);
+}
@@ -0,0 +1,85 @@
+use fa_viz::{
+    AcceptingMarkers, FiniteAutomaton, LabeledTable, ParseOptions, SerializeOptions, TableBuilder,
+};
+
+/// Every row's id, acceptance and targets, in order
+fn rows(table: &LabeledTable) -> Vec<(usize, bool, Vec<Option<usize>>)> {
+    table
+        .table()
+        .rows
+        .iter()
+        .map(|r| (r.id, r.accepting, r.transitions.clone()))
+        .collect()
+}
+
+fn assert_round_trips(table: &LabeledTable) {
+    for align in [false, true] {
+        for accepting_markers in [AcceptingMarkers::PlusMinus, AcceptingMarkers::Binary] {
+            for dead_symbol in ["E", "-", ""] {
+                let options = SerializeOptions {
+                    dead_symbol: dead_symbol.to_owned(),
+                    align,
+                    header: true,
+                    accepting_markers,
+                };
+                let text = table.serialize(&options);
+                let parsed = LabeledTable::parse(&text, &ParseOptions::default())
+                    .unwrap_or_else(|e| panic!("{} does not parse: {}", text, e));
+                assert_eq!(parsed.alphabet(), table.alphabet(), "{}", text);
+                assert_eq!(rows(&parsed), rows(table), "{}", text);
+            }
+        }
+    }
+}
+
+#[test]
+fn binary_alphabet_round_trips() {
+    let table = TableBuilder::new(['0', '1'])
+        .state(0, false, [('0', 1)])
+        .state(1, true, [('0', 1), ('1', 0)])
+        .build()
+        .unwrap();
+    assert_round_trips(&table);
+}
+
+#[test]
+fn marker_alphabet_round_trips() {
+    let table = TableBuilder::new(['+', '-'])
+        .state(0, true, [('-', 1)])
+        .state(1, false, [('+', 0), ('-', 1)])
+        .build()
+        .unwrap();
+    assert_round_trips(&table);
+}
+
+#[test]
+fn unmarked_header_is_still_read() {
+    let table = LabeledTable::parse("a b\n- 0 1 E\n+ 1 E 0\n", &ParseOptions::default()).unwrap();
+    assert_eq!(table.alphabet(), ['a', 'b']);
+    assert_eq!(rows(&table).len(), 2);
+}
+
+#[test]
+fn indented_first_row_is_a_row() {
+    let table = LabeledTable::parse("  - 0 1\n+ 1 0\n", &ParseOptions::default()).unwrap();
+    assert_eq!(
+        rows(&table),
+        [(0, false, vec![Some(1)]), (1, true, vec![Some(0)])]
+    );
+    assert_eq!(table.symbols(), ['a']);
+}
+
+#[test]
+fn binary_header_is_detected_as_a_table() {
+    let table = TableBuilder::new(['0', '1'])
+        .state(0, false, [('0', 1), ('1', 0)])
+        .state(1, true, [('0', 1), ('1', 1)])
+        .build()
+        .unwrap();
+    let text = table.serialize(&SerializeOptions {
+        header: true,
+        ..Default::default()
+    });
+    let frontend = fa_viz::frontend::detect(&text).unwrap();
+    assert_eq!(frontend.name(), "table");
+}
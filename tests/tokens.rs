@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use fa_viz::{DfaRun, Digraph, LabeledTable, NFA, ParseOptions, SerializeOptions};
+
+/// `id := num`, `id := id` and `id || id`
+fn nfa() -> NFA {
+    NFA::from_str("5 & id := num ||\n- 0 1 id\n- 1 2 :=\n- 1 3 ||\n- 2 4 num id\n- 3 4 id\n+ 4 4\n")
+        .unwrap()
+}
+
+#[test]
+fn tokens_are_single_symbols() {
+    let nfa = nfa();
+    assert_eq!(nfa.alphabet().len(), 4);
+    assert_eq!(nfa.tokens().len(), 4);
+    assert!(nfa.accepts_tokens(&["id", ":=", "num"]));
+    assert!(nfa.accepts_tokens(&["id", "||", "id"]));
+    assert!(!nfa.accepts_tokens(&["id", ":=", "||"]));
+    assert!(!nfa.accepts_tokens(&["id", ":", "=", "num"]));
+    assert_eq!(nfa.spell(&["while"]), None);
+}
+
+#[test]
+fn tokens_round_trip() {
+    let nfa = nfa();
+    let text = nfa.serialize();
+    assert!(text.contains(":="), "{}", text);
+    assert_eq!(NFA::from_str(&text).unwrap(), nfa, "{}", text);
+}
+
+#[test]
+fn determinized_tables_keep_tokens() {
+    let table = nfa().determinize();
+    let input = table.spell(&["id", ":=", "id"]).unwrap();
+    let mut run = DfaRun::new(&table, &input);
+    run.finish();
+    assert!(run.accepted());
+
+    let text = table.serialize(&SerializeOptions {
+        header: true,
+        ..Default::default()
+    });
+    let parsed = LabeledTable::parse(&text, &ParseOptions::default())
+        .unwrap_or_else(|e| panic!("{}\n{}", text, e));
+    assert_eq!(parsed.alphabet(), table.alphabet(), "{}", text);
+    assert_eq!(parsed.tokens, table.tokens, "{}", text);
+    assert!(nfa().minimize_brzozowski().pretty_print().contains(":="));
+}
+
+#[test]
+fn edge_labels_name_tokens() {
+    let dot = Digraph::from(&nfa()).to_string();
+    assert!(dot.contains("label=<:=>"), "{}", dot);
+    // Edge labels are split on `|`
+    assert!(dot.contains("label=<&#124;&#124;>"), "{}", dot);
+}
@@ -0,0 +1,92 @@
+//! Assertion helpers for tests of code that embeds automata built with `fa_viz`.
+//!
+//! ```ignore
+//! assert_accepts!(nfa, "abc");
+//! assert_rejects!(nfa, "abd");
+//! assert_equivalent!(nfa, minimized_table);
+//! ```
+
+use fa_viz::{Automaton, LabeledTable, NFA};
+use transition_tables::TransitionTable;
+
+/// Anything that can be viewed as an NFA for simulation
+pub trait AsNfa {
+    fn as_nfa(&self) -> NFA;
+}
+
+impl AsNfa for NFA {
+    fn as_nfa(&self) -> NFA {
+        self.clone()
+    }
+}
+
+impl AsNfa for TransitionTable {
+    fn as_nfa(&self) -> NFA {
+        NFA::from(self)
+    }
+}
+
+impl AsNfa for LabeledTable {
+    fn as_nfa(&self) -> NFA {
+        NFA::from(self)
+    }
+}
+
+impl AsNfa for Automaton {
+    fn as_nfa(&self) -> NFA {
+        self.to_nfa()
+    }
+}
+
+impl<T: AsNfa> AsNfa for &T {
+    fn as_nfa(&self) -> NFA {
+        (*self).as_nfa()
+    }
+}
+
+#[macro_export]
+macro_rules! assert_accepts {
+    ($automaton:expr, $input:expr $(,)?) => {{
+        let input: &str = $input;
+        assert!(
+            $crate::AsNfa::as_nfa(&$automaton).accepts(input),
+            "expected {} to accept {:?}",
+            stringify!($automaton),
+            input
+        );
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_rejects {
+    ($automaton:expr, $input:expr $(,)?) => {{
+        let input: &str = $input;
+        assert!(
+            !$crate::AsNfa::as_nfa(&$automaton).accepts(input),
+            "expected {} to reject {:?}",
+            stringify!($automaton),
+            input
+        );
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_equivalent {
+    ($left:expr, $right:expr $(,)?) => {{
+        let left = $crate::AsNfa::as_nfa(&$left);
+        let right = $crate::AsNfa::as_nfa(&$right);
+        if let Some(word) = left.distinguishing_string(&right) {
+            panic!(
+                "{} and {} differ on {:?}: accepted only by {}",
+                stringify!($left),
+                stringify!($right),
+                word,
+                if left.accepts(&word) {
+                    stringify!($left)
+                } else {
+                    stringify!($right)
+                }
+            );
+        }
+    }};
+}
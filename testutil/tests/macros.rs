@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use fa_viz::{Automaton, NFA};
+use fa_viz_testutil::{assert_accepts, assert_equivalent, assert_rejects};
+
+/// Strings ending in `ab`
+fn ends_in_ab() -> NFA {
+    NFA::from_str("3 # a b\n- 0 0 a b\n- 0 1 a\n- 1 2 b\n+ 2 2\n").unwrap()
+}
+
+#[test]
+fn accepts_and_rejects_every_kind_of_automaton() {
+    let nfa = ends_in_ab();
+    let table = nfa.determinize();
+    let automaton = Automaton::Nfa(nfa.clone());
+    assert_accepts!(nfa, "ab");
+    assert_accepts!(&nfa, "bbab");
+    assert_accepts!(table, "aab");
+    assert_accepts!(table.table(), "aab");
+    assert_accepts!(automaton, "ab");
+    assert_rejects!(nfa, "");
+    assert_rejects!(table, "aba");
+    assert_rejects!(table.table(), "b");
+    assert_rejects!(automaton, "ba");
+}
+
+#[test]
+fn equivalent_across_representations() {
+    let nfa = ends_in_ab();
+    assert_equivalent!(nfa, nfa.determinize());
+    assert_equivalent!(nfa.minimize_brzozowski(), nfa.determinize().table());
+}
+
+#[test]
+#[should_panic(expected = "expected nfa to accept \"ba\"")]
+fn assert_accepts_fails_on_rejected_input() {
+    let nfa = ends_in_ab();
+    assert_accepts!(nfa, "ba");
+}
+
+#[test]
+#[should_panic(expected = "expected nfa to reject \"ab\"")]
+fn assert_rejects_fails_on_accepted_input() {
+    let nfa = ends_in_ab();
+    assert_rejects!(nfa, "ab");
+}
+
+#[test]
+#[should_panic(expected = "nfa and other differ on \"ab\": accepted only by nfa")]
+fn assert_equivalent_names_the_distinguishing_string() {
+    let nfa = ends_in_ab();
+    let other = NFA::from_str("3 # a b\n- 0 0 a b\n- 0 1 b\n- 1 2 a\n+ 2 2\n").unwrap();
+    assert_equivalent!(nfa, other);
+}
@@ -0,0 +1,5 @@
+//! The JavaScript bindings of [`fa_viz::wasm`] as a `cdylib`, built with `wasm-pack build wasm`.
+//! Keeping it a crate of its own leaves `fa_viz` an `rlib` for native users and the command line
+//! tool.
+
+pub use fa_viz::wasm::*;